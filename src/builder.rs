@@ -0,0 +1,131 @@
+use crate::ratelimit::RateLimiter;
+use crate::{Authentication, Cache, Config, RetryPolicy, RevoltRs, RevoltRsError, API_URL};
+use reqwest::header;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+const USER_AGENT: &str = concat!("revolt_rs/", env!("CARGO_PKG_VERSION"));
+
+pub struct RevoltRsBuilder {
+    api_url: String,
+    authentication: Authentication,
+    user_agent: String,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    proxy: Option<reqwest::Proxy>,
+    client: Option<reqwest::Client>,
+    retry_policy: RetryPolicy,
+    cache: Option<Arc<Cache>>,
+}
+
+impl Default for RevoltRsBuilder {
+    fn default() -> Self {
+        RevoltRsBuilder::new()
+    }
+}
+
+impl RevoltRsBuilder {
+    pub fn new() -> Self {
+        RevoltRsBuilder {
+            api_url: API_URL.to_string(),
+            authentication: Authentication::None,
+            user_agent: USER_AGENT.to_string(),
+            timeout: None,
+            connect_timeout: None,
+            proxy: None,
+            client: None,
+            retry_policy: RetryPolicy::default(),
+            cache: None,
+        }
+    }
+    pub fn api_url(mut self, api_url: &str) -> Self {
+        self.api_url = api_url.trim_end_matches('/').to_string();
+        self
+    }
+    /// Authenticates as a bot, sending the token in the `x-bot-token` header.
+    pub fn bot_token(mut self, token: &str) -> Self {
+        self.authentication = Authentication::Bot(token.to_string());
+        self
+    }
+    /// Authenticates as a user, sending the token in the `x-session-token` header.
+    pub fn session_token(mut self, token: &str) -> Self {
+        self.authentication = Authentication::Session(token.to_string());
+        self
+    }
+    pub fn user_agent(mut self, user_agent: &str) -> Self {
+        self.user_agent = user_agent.to_string();
+        self
+    }
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+    /// Only applies when the builder creates the `reqwest::Client` itself.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+    /// Only applies when the builder creates the `reqwest::Client` itself.
+    pub fn proxy(mut self, proxy: reqwest::Proxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+    /// Uses an existing `reqwest::Client` instead of building a new one.
+    /// Authentication, user agent and timeout are still applied per request.
+    pub fn client(mut self, client: reqwest::Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+    /// Sets how transient failures are retried, `RetryPolicy::none()` disables it.
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+    /// Keeps an entity cache, which the bot `Client` fills from gateway events.
+    pub fn cache(mut self, cache: Cache) -> Self {
+        self.cache = Some(Arc::new(cache));
+        self
+    }
+    pub fn build(self) -> Result<RevoltRs, RevoltRsError> {
+        let authentication_header = match &self.authentication {
+            Authentication::Bot(token) => Some(("x-bot-token", token)),
+            Authentication::Session(token) => Some(("x-session-token", token)),
+            Authentication::None => None,
+        }
+        .map(|(name, token)| {
+            let mut value = header::HeaderValue::from_str(token)?;
+            value.set_sensitive(true);
+            Ok::<_, RevoltRsError>((header::HeaderName::from_static(name), value))
+        })
+        .transpose()?;
+        let user_agent = header::HeaderValue::from_str(&self.user_agent)?;
+        let client = match self.client {
+            Some(client) => client,
+            None => {
+                let mut builder = reqwest::Client::builder();
+                if let Some(timeout) = self.connect_timeout {
+                    builder = builder.connect_timeout(timeout);
+                }
+                if let Some(proxy) = self.proxy {
+                    builder = builder.proxy(proxy);
+                }
+                builder.build()?
+            }
+        };
+        Ok(RevoltRs {
+            client,
+            config: Arc::new(Config {
+                api_url: self.api_url,
+                authentication: self.authentication,
+                authentication_header,
+                user_agent,
+                timeout: self.timeout,
+                node: RwLock::new(None),
+                autumn: RwLock::new(None),
+                ratelimiter: RateLimiter::default(),
+                retry_policy: self.retry_policy,
+                cache: self.cache,
+            }),
+        })
+    }
+}
@@ -0,0 +1,777 @@
+use crate::data::event::Event;
+use crate::data::permissions::{ChannelPermission, ServerPermission};
+use crate::data::{channel, message, server, user};
+use crate::RevoltRsError;
+use async_trait::async_trait;
+use std::collections::{HashMap, VecDeque};
+use std::sync::RwLock;
+
+mod file;
+mod memory;
+
+pub use self::file::FileBackend;
+pub use self::memory::MemoryBackend;
+
+const MESSAGE_LIMIT: usize = 100;
+
+/// Where the cache keeps users, channels, servers and members.
+#[async_trait]
+pub trait CacheBackend: Send + Sync {
+    async fn user(&self, user_id: &str) -> Result<Option<user::User>, RevoltRsError>;
+    async fn insert_user(&self, user: user::User) -> Result<(), RevoltRsError>;
+    async fn remove_user(&self, user_id: &str) -> Result<(), RevoltRsError>;
+    async fn channel(&self, channel_id: &str) -> Result<Option<channel::Channel>, RevoltRsError>;
+    /// Every cached channel.
+    async fn channels(&self) -> Result<Vec<channel::Channel>, RevoltRsError>;
+    async fn insert_channel(&self, channel: channel::Channel) -> Result<(), RevoltRsError>;
+    async fn remove_channel(&self, channel_id: &str) -> Result<(), RevoltRsError>;
+    async fn server(&self, server_id: &str) -> Result<Option<server::Server>, RevoltRsError>;
+    /// Every cached server.
+    async fn servers(&self) -> Result<Vec<server::Server>, RevoltRsError>;
+    async fn insert_server(&self, server: server::Server) -> Result<(), RevoltRsError>;
+    async fn remove_server(&self, server_id: &str) -> Result<(), RevoltRsError>;
+    async fn member(
+        &self,
+        server_id: &str,
+        user_id: &str,
+    ) -> Result<Option<server::Member>, RevoltRsError>;
+    /// Every cached member of a server.
+    async fn members(&self, server_id: &str) -> Result<Vec<server::Member>, RevoltRsError>;
+    async fn insert_member(&self, member: server::Member) -> Result<(), RevoltRsError>;
+    async fn remove_member(&self, server_id: &str, user_id: &str) -> Result<(), RevoltRsError>;
+    /// Removes the members of every server.
+    async fn clear_members(&self) -> Result<(), RevoltRsError>;
+}
+
+/// A copy of the users, channels, servers, members and recent messages the
+/// client has seen, kept up to date from gateway events.
+///
+/// Entities live in a `CacheBackend`, in memory unless another backend is
+/// given. Recent messages are always kept in memory.
+pub struct Cache {
+    backend: Box<dyn CacheBackend>,
+    messages: RwLock<HashMap<String, VecDeque<message::Message>>>,
+    message_limit: usize,
+}
+
+impl Default for Cache {
+    fn default() -> Self {
+        Cache::new()
+    }
+}
+
+impl Cache {
+    pub fn new() -> Self {
+        Cache::with_backend(MemoryBackend::default())
+    }
+    pub fn with_backend<B: CacheBackend + 'static>(backend: B) -> Self {
+        Cache {
+            backend: Box::new(backend),
+            messages: RwLock::default(),
+            message_limit: MESSAGE_LIMIT,
+        }
+    }
+    /// Keeps at most `message_limit` messages per channel.
+    pub fn with_message_limit(mut self, message_limit: usize) -> Self {
+        self.message_limit = message_limit;
+        self
+    }
+    pub fn backend(&self) -> &dyn CacheBackend {
+        &*self.backend
+    }
+    pub async fn user(&self, user_id: &str) -> Result<Option<user::User>, RevoltRsError> {
+        self.backend.user(user_id).await
+    }
+    pub async fn channel(
+        &self,
+        channel_id: &str,
+    ) -> Result<Option<channel::Channel>, RevoltRsError> {
+        self.backend.channel(channel_id).await
+    }
+    pub async fn server(&self, server_id: &str) -> Result<Option<server::Server>, RevoltRsError> {
+        self.backend.server(server_id).await
+    }
+    pub async fn member(
+        &self,
+        server_id: &str,
+        user_id: &str,
+    ) -> Result<Option<server::Member>, RevoltRsError> {
+        self.backend.member(server_id, user_id).await
+    }
+    pub fn message(&self, channel_id: &str, message_id: &str) -> Option<message::Message> {
+        let messages = self.messages.read().unwrap();
+        let message = messages
+            .get(channel_id)?
+            .iter()
+            .find(|message| message.id == message_id)?;
+        Some(message.clone())
+    }
+    /// The cached messages of a channel, oldest first.
+    pub fn messages(&self, channel_id: &str) -> Vec<message::Message> {
+        let messages = self.messages.read().unwrap();
+        messages
+            .get(channel_id)
+            .map(|messages| messages.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+    pub async fn insert_user(&self, user: user::User) -> Result<(), RevoltRsError> {
+        self.backend.insert_user(user).await
+    }
+    pub async fn insert_channel(&self, channel: channel::Channel) -> Result<(), RevoltRsError> {
+        self.backend.insert_channel(channel).await
+    }
+    pub async fn insert_server(&self, server: server::Server) -> Result<(), RevoltRsError> {
+        self.backend.insert_server(server).await
+    }
+    pub async fn insert_member(&self, member: server::Member) -> Result<(), RevoltRsError> {
+        self.backend.insert_member(member).await
+    }
+    pub fn insert_message(&self, message: message::Message) {
+        let mut messages = self.messages.write().unwrap();
+        let channel = messages.entry(message.channel.clone()).or_default();
+        channel.push_back(message);
+        while channel.len() > self.message_limit {
+            channel.pop_front();
+        }
+    }
+    /// Applies a gateway event to the cached entities.
+    pub async fn update(&self, event: &Event) -> Result<(), RevoltRsError> {
+        let backend = &self.backend;
+        match event {
+            Event::Ready {
+                users,
+                servers,
+                channels,
+                members,
+            } => {
+                self.forget(servers, channels).await?;
+                for user in users {
+                    backend.insert_user(user.clone()).await?;
+                }
+                for server in servers {
+                    backend.insert_server(server.clone()).await?;
+                }
+                for channel in channels {
+                    backend.insert_channel(channel.clone()).await?;
+                }
+                for member in members.iter().flatten() {
+                    backend.insert_member(member.clone()).await?;
+                }
+            }
+            // Events were missed, members and the roles and permissions of
+            // servers and channels may be wrong until the `Ready` that
+            // follows every reconnection.
+            Event::Reconnected => self.forget(&[], &[]).await?,
+            Event::Message(message) => self.insert_message(message.clone()),
+            Event::MessageUpdate { id, channel, data } => {
+                let mut messages = self.messages.write().unwrap();
+                let message = messages
+                    .get_mut(channel)
+                    .and_then(|messages| messages.iter_mut().find(|message| &message.id == id));
+                if let Some(message) = message {
+                    apply_message(message, data);
+                }
+            }
+            Event::MessageDelete { id, channel } => {
+                if let Some(messages) = self.messages.write().unwrap().get_mut(channel) {
+                    messages.retain(|message| &message.id != id);
+                }
+            }
+            Event::ChannelCreate(channel) => {
+                if let Some(server_id) = &channel.server {
+                    if let Some(mut server) = backend.server(server_id).await? {
+                        if !server.channels.contains(&channel.id) {
+                            server.channels.push(channel.id.clone());
+                            backend.insert_server(server).await?;
+                        }
+                    }
+                }
+                backend.insert_channel(channel.clone()).await?;
+            }
+            Event::ChannelUpdate { id, data, clear } => {
+                if let Some(mut channel) = backend.channel(id).await? {
+                    apply_channel(&mut channel, data, clear.as_ref());
+                    backend.insert_channel(channel).await?;
+                }
+            }
+            Event::ChannelDelete { id } => self.remove_channel(id).await?,
+            Event::ChannelGroupJoin { id, user } => {
+                if let Some(mut channel) = backend.channel(id).await? {
+                    let recipients = channel.recipients.get_or_insert_with(Vec::new);
+                    if !recipients.contains(user) {
+                        recipients.push(user.clone());
+                    }
+                    backend.insert_channel(channel).await?;
+                }
+            }
+            Event::ChannelGroupLeave { id, user } => {
+                if let Some(mut channel) = backend.channel(id).await? {
+                    if let Some(recipients) = &mut channel.recipients {
+                        recipients.retain(|recipient| recipient != user);
+                    }
+                    backend.insert_channel(channel).await?;
+                }
+            }
+            Event::ServerUpdate { id, data, clear } => {
+                if let Some(mut server) = backend.server(id).await? {
+                    apply_server(&mut server, data, clear.as_ref());
+                    backend.insert_server(server).await?;
+                }
+            }
+            Event::ServerDelete { id } => {
+                if let Some(server) = backend.server(id).await? {
+                    for channel in &server.channels {
+                        self.remove_channel(channel).await?;
+                    }
+                    backend.remove_server(id).await?;
+                }
+                for member in backend.members(id).await? {
+                    backend.remove_member(id, &member.id.user).await?;
+                }
+            }
+            Event::ServerMemberJoin { id, user } => {
+                let member = server::Member {
+                    id: server::MemberId {
+                        server: id.clone(),
+                        user: user.clone(),
+                    },
+                    nickname: None,
+                    avatar: None,
+                    roles: None,
+                };
+                backend.insert_member(member).await?;
+            }
+            Event::ServerMemberLeave { id, user } => backend.remove_member(id, user).await?,
+            Event::ServerMemberUpdate { id, data, clear } => {
+                if let Some(mut member) = backend.member(&id.server, &id.user).await? {
+                    apply_member(&mut member, data, clear.as_ref());
+                    backend.insert_member(member).await?;
+                }
+            }
+            Event::ServerRoleUpdate {
+                id,
+                role_id,
+                data,
+                clear,
+            } => {
+                if let Some(mut server) = backend.server(id).await? {
+                    let role = server
+                        .roles
+                        .get_or_insert_with(HashMap::new)
+                        .entry(role_id.clone())
+                        .or_insert_with(|| server::Role {
+                            name: String::new(),
+                            permissions: (ServerPermission::empty(), ChannelPermission::empty()),
+                            colour: None,
+                            hoist: None,
+                            rank: None,
+                        });
+                    apply_role(role, data, clear.as_ref());
+                    backend.insert_server(server).await?;
+                }
+            }
+            Event::ServerRoleDelete { id, role_id } => {
+                if let Some(mut server) = backend.server(id).await? {
+                    if let Some(roles) = &mut server.roles {
+                        roles.remove(role_id);
+                    }
+                    backend.insert_server(server).await?;
+                }
+                for mut member in backend.members(id).await? {
+                    if let Some(roles) = &mut member.roles {
+                        if roles.contains(role_id) {
+                            roles.retain(|role| role != role_id);
+                            backend.insert_member(member).await?;
+                        }
+                    }
+                }
+            }
+            Event::UserUpdate { id, data, clear } => {
+                if let Some(mut user) = backend.user(id).await? {
+                    apply_user(&mut user, data, clear.as_ref());
+                    backend.insert_user(user).await?;
+                }
+            }
+            Event::UserRelationship { user, status, .. } => {
+                let mut user = user.clone();
+                user.relationship = Some(status.clone());
+                backend.insert_user(user).await?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+    /// Drops the servers and channels that are not in `servers` and
+    /// `channels`, and every member.
+    ///
+    /// `Ready` lists every server and channel the client is in, anything else
+    /// was deleted or left while the client was offline. Members are only
+    /// partly listed and the roles of the others are of unknown age, so they
+    /// are fetched again when needed. Dropped channels lose their messages.
+    async fn forget(
+        &self,
+        servers: &[server::Server],
+        channels: &[channel::Channel],
+    ) -> Result<(), RevoltRsError> {
+        for server in self.backend.servers().await? {
+            if !servers.iter().any(|kept| kept.id == server.id) {
+                self.backend.remove_server(&server.id).await?;
+            }
+        }
+        for channel in self.backend.channels().await? {
+            if !channels.iter().any(|kept| kept.id == channel.id) {
+                self.messages.write().unwrap().remove(&channel.id);
+                self.backend.remove_channel(&channel.id).await?;
+            }
+        }
+        self.backend.clear_members().await
+    }
+    async fn remove_channel(&self, channel_id: &str) -> Result<(), RevoltRsError> {
+        let channel = self.backend.channel(channel_id).await?;
+        if let Some(server_id) = channel.and_then(|channel| channel.server) {
+            if let Some(mut server) = self.backend.server(&server_id).await? {
+                server.channels.retain(|channel| channel != channel_id);
+                self.backend.insert_server(server).await?;
+            }
+        }
+        self.messages.write().unwrap().remove(channel_id);
+        self.backend.remove_channel(channel_id).await
+    }
+}
+
+fn apply_message(message: &mut message::Message, data: &message::PartialMessage) {
+    if let Some(content) = &data.content {
+        message.content = Some(content.clone());
+    }
+    if let Some(edited) = &data.edited {
+        message.edited = Some(edited.clone());
+    }
+    if let Some(embeds) = &data.embeds {
+        message.embeds = Some(embeds.clone());
+    }
+}
+
+fn apply_channel(
+    channel: &mut channel::Channel,
+    data: &channel::PartialChannel,
+    clear: Option<&channel::ChannelField>,
+) {
+    let data = data.clone();
+    channel.name = data.name.or_else(|| channel.name.take());
+    channel.owner = data.owner.or_else(|| channel.owner.take());
+    channel.description = data.description.or_else(|| channel.description.take());
+    channel.icon = data.icon.or_else(|| channel.icon.take());
+    channel.recipients = data.recipients.or_else(|| channel.recipients.take());
+    channel.active = data.active.or(channel.active);
+    channel.last_message = data.last_message.or_else(|| channel.last_message.take());
+    channel.default_permissions = data.default_permissions.or(channel.default_permissions);
+    channel.role_permissions = data
+        .role_permissions
+        .or_else(|| channel.role_permissions.take());
+    channel.permissions = data.permissions.or(channel.permissions);
+    match clear {
+        Some(channel::ChannelField::Icon) => channel.icon = None,
+        Some(channel::ChannelField::Description) => channel.description = None,
+        None => {}
+    }
+}
+
+fn apply_server(
+    server: &mut server::Server,
+    data: &server::PartialServer,
+    clear: Option<&server::ServerField>,
+) {
+    let data = data.clone();
+    if let Some(owner) = data.owner {
+        server.owner = owner;
+    }
+    if let Some(name) = data.name {
+        server.name = name;
+    }
+    if let Some(channels) = data.channels {
+        server.channels = channels;
+    }
+    if let Some(default_permissions) = data.default_permissions {
+        server.default_permissions = default_permissions;
+    }
+    server.description = data.description.or_else(|| server.description.take());
+    server.categories = data.categories.or_else(|| server.categories.take());
+    server.system_messages = data
+        .system_messages
+        .or_else(|| server.system_messages.take());
+    server.icon = data.icon.or_else(|| server.icon.take());
+    server.banner = data.banner.or_else(|| server.banner.take());
+    match clear {
+        Some(server::ServerField::Icon) => server.icon = None,
+        Some(server::ServerField::Banner) => server.banner = None,
+        Some(server::ServerField::Description) => server.description = None,
+        None => {}
+    }
+}
+
+fn apply_member(
+    member: &mut server::Member,
+    data: &server::PartialMember,
+    clear: Option<&server::MemberField>,
+) {
+    let data = data.clone();
+    member.nickname = data.nickname.or_else(|| member.nickname.take());
+    member.avatar = data.avatar.or_else(|| member.avatar.take());
+    member.roles = data.roles.or_else(|| member.roles.take());
+    match clear {
+        Some(server::MemberField::Nickname) => member.nickname = None,
+        Some(server::MemberField::Avatar) => member.avatar = None,
+        None => {}
+    }
+}
+
+fn apply_role(
+    role: &mut server::Role,
+    data: &server::PartialRole,
+    clear: Option<&server::RoleField>,
+) {
+    let data = data.clone();
+    if let Some(name) = data.name {
+        role.name = name;
+    }
+    if let Some(permissions) = data.permissions {
+        role.permissions = permissions;
+    }
+    role.colour = data.colour.or_else(|| role.colour.take());
+    role.hoist = data.hoist.or(role.hoist);
+    role.rank = data.rank.or(role.rank);
+    if let Some(server::RoleField::Colour) = clear {
+        role.colour = None;
+    }
+}
+
+fn apply_user(user: &mut user::User, data: &user::PartialUser, clear: Option<&user::UserField>) {
+    let data = data.clone();
+    if let Some(username) = data.username {
+        user.username = username;
+    }
+    user.avatar = data.avatar.or_else(|| user.avatar.take());
+    user.badges = data.badges.or(user.badges);
+    user.status = data.status.or_else(|| user.status.take());
+    user.online = data.online.or(user.online);
+    user.flags = data.flags.or(user.flags);
+    match clear {
+        Some(user::UserField::Avatar) => user.avatar = None,
+        Some(user::UserField::StatusText) => {
+            if let Some(status) = &mut user.status {
+                status.text = None;
+            }
+        }
+        Some(user::UserField::ProfileContent) | Some(user::UserField::ProfileBackground) | None => {
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    async fn update(cache: &Cache, event: serde_json::Value) {
+        cache
+            .update(&serde_json::from_value(event).unwrap())
+            .await
+            .unwrap();
+    }
+
+    fn server(id: &str, channels: &[&str]) -> serde_json::Value {
+        json!({
+            "_id": id,
+            "owner": "OWNER",
+            "name": "server",
+            "channels": channels,
+            "roles": {
+                "MOD": { "name": "mod", "permissions": [1, 1], "colour": "red", "rank": 1 },
+                "ADMIN": { "name": "admin", "permissions": [63, 511], "rank": 0 },
+            },
+            "default_permissions": [1, 3],
+        })
+    }
+
+    fn channel(id: &str, server: &str) -> serde_json::Value {
+        json!({
+            "_id": id,
+            "server": server,
+            "channel_type": "TextChannel",
+            "name": "general",
+            "description": "about",
+        })
+    }
+
+    fn member(server: &str, user: &str, roles: &[&str]) -> serde_json::Value {
+        json!({ "_id": { "server": server, "user": user }, "nickname": "nick", "roles": roles })
+    }
+
+    fn message(id: &str, channel: &str, content: &str) -> serde_json::Value {
+        json!({ "type": "Message", "_id": id, "channel": channel, "author": "USER", "content": content })
+    }
+
+    /// A cache holding server `SERVER` with channel `CHANNEL` and two members.
+    async fn cache() -> Cache {
+        let cache = Cache::new();
+        update(
+            &cache,
+            json!({
+                "type": "Ready",
+                "users": [{ "_id": "USER", "username": "user", "status": { "text": "hi" } }],
+                "servers": [server("SERVER", &["CHANNEL"])],
+                "channels": [channel("CHANNEL", "SERVER")],
+                "members": [
+                    member("SERVER", "USER", &["MOD", "ADMIN"]),
+                    member("SERVER", "OTHER", &["MOD"]),
+                ],
+            }),
+        )
+        .await;
+        cache
+    }
+
+    #[async_std::test]
+    async fn ready_drops_what_it_does_not_list() {
+        let cache = cache().await;
+        cache
+            .insert_member(serde_json::from_value(member("SERVER", "GONE", &[])).unwrap())
+            .await
+            .unwrap();
+        cache.insert_message(serde_json::from_value(message("M", "CHANNEL", "a")).unwrap());
+        update(
+            &cache,
+            json!({
+                "type": "Ready",
+                "users": [],
+                "servers": [],
+                "channels": [],
+            }),
+        )
+        .await;
+        assert!(cache.server("SERVER").await.unwrap().is_none());
+        assert!(cache.channel("CHANNEL").await.unwrap().is_none());
+        assert!(cache.member("SERVER", "USER").await.unwrap().is_none());
+        assert!(cache.member("SERVER", "GONE").await.unwrap().is_none());
+        assert!(cache.messages("CHANNEL").is_empty());
+        assert!(cache.user("USER").await.unwrap().is_some());
+    }
+
+    #[async_std::test]
+    async fn reconnecting_forgets_members_and_roles() {
+        let cache = cache().await;
+        update(&cache, json!({ "type": "Reconnected" })).await;
+        assert!(cache.server("SERVER").await.unwrap().is_none());
+        assert!(cache.channel("CHANNEL").await.unwrap().is_none());
+        assert!(cache.member("SERVER", "USER").await.unwrap().is_none());
+        assert!(cache.user("USER").await.unwrap().is_some());
+    }
+
+    #[async_std::test]
+    async fn member_updates_apply_data_then_clear() {
+        let cache = cache().await;
+        update(
+            &cache,
+            json!({
+                "type": "ServerMemberUpdate",
+                "id": { "server": "SERVER", "user": "USER" },
+                "data": { "roles": ["ADMIN"] },
+                "clear": "Nickname",
+            }),
+        )
+        .await;
+        let member = cache.member("SERVER", "USER").await.unwrap().unwrap();
+        assert_eq!(member.roles.unwrap(), ["ADMIN"]);
+        assert!(member.nickname.is_none());
+    }
+
+    #[async_std::test]
+    async fn deleted_roles_are_removed_from_members() {
+        let cache = cache().await;
+        update(
+            &cache,
+            json!({ "type": "ServerRoleDelete", "id": "SERVER", "role_id": "MOD" }),
+        )
+        .await;
+        let server = cache.server("SERVER").await.unwrap().unwrap();
+        let roles = server.roles.unwrap();
+        assert!(!roles.contains_key("MOD"));
+        assert!(roles.contains_key("ADMIN"));
+        let member = cache.member("SERVER", "USER").await.unwrap().unwrap();
+        assert_eq!(member.roles.unwrap(), ["ADMIN"]);
+        let other = cache.member("SERVER", "OTHER").await.unwrap().unwrap();
+        assert!(other.roles.unwrap().is_empty());
+    }
+
+    #[async_std::test]
+    async fn role_updates_create_and_clear() {
+        let cache = cache().await;
+        update(
+            &cache,
+            json!({
+                "type": "ServerRoleUpdate",
+                "id": "SERVER",
+                "role_id": "MOD",
+                "data": { "permissions": [0, 2] },
+                "clear": "Colour",
+            }),
+        )
+        .await;
+        update(
+            &cache,
+            json!({
+                "type": "ServerRoleUpdate",
+                "id": "SERVER",
+                "role_id": "NEW",
+                "data": { "name": "new", "rank": 2 },
+            }),
+        )
+        .await;
+        let roles = cache
+            .server("SERVER")
+            .await
+            .unwrap()
+            .unwrap()
+            .roles
+            .unwrap();
+        let moderator = &roles["MOD"];
+        assert_eq!(moderator.name, "mod");
+        assert_eq!(moderator.permissions.1, ChannelPermission::SEND_MESSAGE);
+        assert!(moderator.colour.is_none());
+        assert_eq!(roles["NEW"].name, "new");
+        assert_eq!(roles["NEW"].rank, Some(2));
+    }
+
+    #[async_std::test]
+    async fn server_updates_apply_data_then_clear() {
+        let cache = cache().await;
+        update(
+            &cache,
+            json!({
+                "type": "ServerUpdate",
+                "id": "SERVER",
+                "data": { "name": "renamed", "description": "new" },
+                "clear": "Description",
+            }),
+        )
+        .await;
+        let server = cache.server("SERVER").await.unwrap().unwrap();
+        assert_eq!(server.name, "renamed");
+        assert!(server.description.is_none());
+        assert_eq!(server.channels, ["CHANNEL"]);
+    }
+
+    #[async_std::test]
+    async fn channels_are_tracked_on_their_server() {
+        let cache = cache().await;
+        let mut created = channel("NEW", "SERVER");
+        created["type"] = json!("ChannelCreate");
+        update(&cache, created).await;
+        let server = cache.server("SERVER").await.unwrap().unwrap();
+        assert_eq!(server.channels, ["CHANNEL", "NEW"]);
+
+        update(
+            &cache,
+            json!({
+                "type": "ChannelUpdate",
+                "id": "NEW",
+                "data": { "name": "renamed" },
+                "clear": "Description",
+            }),
+        )
+        .await;
+        let channel = cache.channel("NEW").await.unwrap().unwrap();
+        assert_eq!(channel.name.as_deref(), Some("renamed"));
+        assert!(channel.description.is_none());
+
+        cache.insert_message(serde_json::from_value(message("M", "NEW", "a")).unwrap());
+        update(&cache, json!({ "type": "ChannelDelete", "id": "NEW" })).await;
+        assert!(cache.channel("NEW").await.unwrap().is_none());
+        assert!(cache.messages("NEW").is_empty());
+        let server = cache.server("SERVER").await.unwrap().unwrap();
+        assert_eq!(server.channels, ["CHANNEL"]);
+    }
+
+    #[async_std::test]
+    async fn deleting_a_server_removes_its_channels_and_members() {
+        let cache = cache().await;
+        update(&cache, json!({ "type": "ServerDelete", "id": "SERVER" })).await;
+        assert!(cache.server("SERVER").await.unwrap().is_none());
+        assert!(cache.channel("CHANNEL").await.unwrap().is_none());
+        assert!(cache.backend().members("SERVER").await.unwrap().is_empty());
+    }
+
+    #[async_std::test]
+    async fn members_join_and_leave() {
+        let cache = cache().await;
+        update(
+            &cache,
+            json!({ "type": "ServerMemberJoin", "id": "SERVER", "user": "NEW" }),
+        )
+        .await;
+        assert!(cache.member("SERVER", "NEW").await.unwrap().is_some());
+        update(
+            &cache,
+            json!({ "type": "ServerMemberLeave", "id": "SERVER", "user": "NEW" }),
+        )
+        .await;
+        assert!(cache.member("SERVER", "NEW").await.unwrap().is_none());
+    }
+
+    #[async_std::test]
+    async fn user_updates_apply_data_then_clear() {
+        let cache = cache().await;
+        update(
+            &cache,
+            json!({
+                "type": "UserUpdate",
+                "id": "USER",
+                "data": { "username": "renamed", "online": true },
+                "clear": "StatusText",
+            }),
+        )
+        .await;
+        let user = cache.user("USER").await.unwrap().unwrap();
+        assert_eq!(user.username, "renamed");
+        assert_eq!(user.online, Some(true));
+        assert!(user.status.unwrap().text.is_none());
+    }
+
+    #[async_std::test]
+    async fn messages_are_updated_deleted_and_limited() {
+        let cache = cache().await.with_message_limit(2);
+        for id in &["A", "B", "C"] {
+            update(&cache, message(id, "CHANNEL", id)).await;
+        }
+        let ids: Vec<String> = cache
+            .messages("CHANNEL")
+            .into_iter()
+            .map(|message| message.id)
+            .collect();
+        assert_eq!(ids, ["B", "C"]);
+
+        update(
+            &cache,
+            json!({
+                "type": "MessageUpdate",
+                "id": "B",
+                "channel": "CHANNEL",
+                "data": { "content": "edited" },
+            }),
+        )
+        .await;
+        let edited = cache.message("CHANNEL", "B").unwrap();
+        assert!(matches!(
+            edited.content,
+            Some(message::ContentType::B(content)) if content == "edited"
+        ));
+
+        update(
+            &cache,
+            json!({ "type": "MessageDelete", "id": "C", "channel": "CHANNEL" }),
+        )
+        .await;
+        assert!(cache.message("CHANNEL", "C").is_none());
+        assert_eq!(cache.messages("CHANNEL").len(), 1);
+    }
+}
@@ -0,0 +1,164 @@
+use crate::cache::CacheBackend;
+use crate::data::{channel, server, user};
+use crate::RevoltRsError;
+use async_std::fs;
+use async_std::io::ErrorKind;
+use async_trait::async_trait;
+use futures::StreamExt;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Keeps cached entities as JSON files in a directory, so they survive a
+/// restart of the bot.
+///
+/// Each entity is stored at `<directory>/<kind>/<id>.json`, members at
+/// `<directory>/members/<server id>/<user id>.json`. Servers and channels the
+/// `Ready` event no longer lists and all members are dropped when it arrives,
+/// so nothing that changed while the bot was offline is served.
+#[derive(Debug, Clone)]
+pub struct FileBackend {
+    directory: PathBuf,
+}
+
+impl FileBackend {
+    pub fn new<P: Into<PathBuf>>(directory: P) -> Self {
+        FileBackend {
+            directory: directory.into(),
+        }
+    }
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+    fn path(&self, kind: &str, ids: &[&str]) -> Result<PathBuf, RevoltRsError> {
+        let mut path = self.directory.join(kind);
+        for id in ids {
+            if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric()) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("{0:?} is not a valid id", id),
+                )
+                .into());
+            }
+            path.push(id);
+        }
+        path.set_extension("json");
+        Ok(path)
+    }
+}
+
+async fn read<T: DeserializeOwned>(path: &Path) -> Result<Option<T>, RevoltRsError> {
+    match fs::read_to_string(path).await {
+        Ok(data) => Ok(Some(serde_json::from_str(&data)?)),
+        Err(error) if error.kind() == ErrorKind::NotFound => Ok(None),
+        Err(error) => Err(error.into()),
+    }
+}
+
+/// Reads every entity stored in a directory.
+async fn read_all<T: DeserializeOwned>(directory: &Path) -> Result<Vec<T>, RevoltRsError> {
+    let mut entries = match fs::read_dir(directory).await {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(error.into()),
+    };
+    let mut entities = Vec::new();
+    while let Some(entry) = entries.next().await {
+        let path: PathBuf = entry?.path().into();
+        if path
+            .extension()
+            .is_some_and(|extension| extension == "json")
+        {
+            entities.extend(read(&path).await?);
+        }
+    }
+    Ok(entities)
+}
+
+async fn write<T: Serialize>(path: &Path, value: &T) -> Result<(), RevoltRsError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).await?;
+    }
+    let data = serde_json::to_string(value)?;
+    // Every write gets its own temporary file, so concurrent writes to the
+    // same entity cannot rename each other's half written files into place.
+    let extension = format!("json.{0}.tmp", rusty_ulid::generate_ulid_string());
+    let temporary = path.with_extension(extension);
+    let written = match fs::write(&temporary, data).await {
+        Ok(()) => fs::rename(&temporary, path).await,
+        Err(error) => Err(error),
+    };
+    if let Err(error) = written {
+        let _ = fs::remove_file(&temporary).await;
+        return Err(error.into());
+    }
+    Ok(())
+}
+
+async fn remove(path: &Path) -> Result<(), RevoltRsError> {
+    match fs::remove_file(path).await {
+        Err(error) if error.kind() != ErrorKind::NotFound => Err(error.into()),
+        _ => Ok(()),
+    }
+}
+
+#[async_trait]
+impl CacheBackend for FileBackend {
+    async fn user(&self, user_id: &str) -> Result<Option<user::User>, RevoltRsError> {
+        read(&self.path("users", &[user_id])?).await
+    }
+    async fn insert_user(&self, user: user::User) -> Result<(), RevoltRsError> {
+        write(&self.path("users", &[&user.id])?, &user).await
+    }
+    async fn remove_user(&self, user_id: &str) -> Result<(), RevoltRsError> {
+        remove(&self.path("users", &[user_id])?).await
+    }
+    async fn channel(&self, channel_id: &str) -> Result<Option<channel::Channel>, RevoltRsError> {
+        read(&self.path("channels", &[channel_id])?).await
+    }
+    async fn channels(&self) -> Result<Vec<channel::Channel>, RevoltRsError> {
+        read_all(&self.directory.join("channels")).await
+    }
+    async fn insert_channel(&self, channel: channel::Channel) -> Result<(), RevoltRsError> {
+        write(&self.path("channels", &[&channel.id])?, &channel).await
+    }
+    async fn remove_channel(&self, channel_id: &str) -> Result<(), RevoltRsError> {
+        remove(&self.path("channels", &[channel_id])?).await
+    }
+    async fn server(&self, server_id: &str) -> Result<Option<server::Server>, RevoltRsError> {
+        read(&self.path("servers", &[server_id])?).await
+    }
+    async fn servers(&self) -> Result<Vec<server::Server>, RevoltRsError> {
+        read_all(&self.directory.join("servers")).await
+    }
+    async fn insert_server(&self, server: server::Server) -> Result<(), RevoltRsError> {
+        write(&self.path("servers", &[&server.id])?, &server).await
+    }
+    async fn remove_server(&self, server_id: &str) -> Result<(), RevoltRsError> {
+        remove(&self.path("servers", &[server_id])?).await
+    }
+    async fn member(
+        &self,
+        server_id: &str,
+        user_id: &str,
+    ) -> Result<Option<server::Member>, RevoltRsError> {
+        read(&self.path("members", &[server_id, user_id])?).await
+    }
+    async fn members(&self, server_id: &str) -> Result<Vec<server::Member>, RevoltRsError> {
+        read_all(&self.path("members", &[server_id])?.with_extension("")).await
+    }
+    async fn insert_member(&self, member: server::Member) -> Result<(), RevoltRsError> {
+        let path = self.path("members", &[&member.id.server, &member.id.user])?;
+        write(&path, &member).await
+    }
+    async fn remove_member(&self, server_id: &str, user_id: &str) -> Result<(), RevoltRsError> {
+        remove(&self.path("members", &[server_id, user_id])?).await
+    }
+    async fn clear_members(&self) -> Result<(), RevoltRsError> {
+        match fs::remove_dir_all(self.directory.join("members")).await {
+            Err(error) if error.kind() != ErrorKind::NotFound => Err(error.into()),
+            _ => Ok(()),
+        }
+    }
+}
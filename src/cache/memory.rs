@@ -0,0 +1,94 @@
+use crate::cache::CacheBackend;
+use crate::data::{channel, server, user};
+use crate::RevoltRsError;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+/// Keeps cached entities in memory, they are lost when the bot stops.
+#[derive(Debug, Default)]
+pub struct MemoryBackend {
+    users: RwLock<HashMap<String, user::User>>,
+    channels: RwLock<HashMap<String, channel::Channel>>,
+    servers: RwLock<HashMap<String, server::Server>>,
+    members: RwLock<HashMap<(String, String), server::Member>>,
+}
+
+#[async_trait]
+impl CacheBackend for MemoryBackend {
+    async fn user(&self, user_id: &str) -> Result<Option<user::User>, RevoltRsError> {
+        Ok(self.users.read().unwrap().get(user_id).cloned())
+    }
+    async fn insert_user(&self, user: user::User) -> Result<(), RevoltRsError> {
+        self.users.write().unwrap().insert(user.id.clone(), user);
+        Ok(())
+    }
+    async fn remove_user(&self, user_id: &str) -> Result<(), RevoltRsError> {
+        self.users.write().unwrap().remove(user_id);
+        Ok(())
+    }
+    async fn channel(&self, channel_id: &str) -> Result<Option<channel::Channel>, RevoltRsError> {
+        Ok(self.channels.read().unwrap().get(channel_id).cloned())
+    }
+    async fn channels(&self) -> Result<Vec<channel::Channel>, RevoltRsError> {
+        Ok(self.channels.read().unwrap().values().cloned().collect())
+    }
+    async fn insert_channel(&self, channel: channel::Channel) -> Result<(), RevoltRsError> {
+        self.channels
+            .write()
+            .unwrap()
+            .insert(channel.id.clone(), channel);
+        Ok(())
+    }
+    async fn remove_channel(&self, channel_id: &str) -> Result<(), RevoltRsError> {
+        self.channels.write().unwrap().remove(channel_id);
+        Ok(())
+    }
+    async fn server(&self, server_id: &str) -> Result<Option<server::Server>, RevoltRsError> {
+        Ok(self.servers.read().unwrap().get(server_id).cloned())
+    }
+    async fn servers(&self) -> Result<Vec<server::Server>, RevoltRsError> {
+        Ok(self.servers.read().unwrap().values().cloned().collect())
+    }
+    async fn insert_server(&self, server: server::Server) -> Result<(), RevoltRsError> {
+        self.servers
+            .write()
+            .unwrap()
+            .insert(server.id.clone(), server);
+        Ok(())
+    }
+    async fn remove_server(&self, server_id: &str) -> Result<(), RevoltRsError> {
+        self.servers.write().unwrap().remove(server_id);
+        Ok(())
+    }
+    async fn member(
+        &self,
+        server_id: &str,
+        user_id: &str,
+    ) -> Result<Option<server::Member>, RevoltRsError> {
+        let key = (server_id.to_string(), user_id.to_string());
+        Ok(self.members.read().unwrap().get(&key).cloned())
+    }
+    async fn members(&self, server_id: &str) -> Result<Vec<server::Member>, RevoltRsError> {
+        let members = self.members.read().unwrap();
+        Ok(members
+            .values()
+            .filter(|member| member.id.server == server_id)
+            .cloned()
+            .collect())
+    }
+    async fn insert_member(&self, member: server::Member) -> Result<(), RevoltRsError> {
+        let key = (member.id.server.clone(), member.id.user.clone());
+        self.members.write().unwrap().insert(key, member);
+        Ok(())
+    }
+    async fn remove_member(&self, server_id: &str, user_id: &str) -> Result<(), RevoltRsError> {
+        let key = (server_id.to_string(), user_id.to_string());
+        self.members.write().unwrap().remove(&key);
+        Ok(())
+    }
+    async fn clear_members(&self) -> Result<(), RevoltRsError> {
+        self.members.write().unwrap().clear();
+        Ok(())
+    }
+}
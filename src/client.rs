@@ -0,0 +1,218 @@
+use crate::data::{channel, event::Event, message, server, user};
+use crate::{Cache, Gateway, RevoltRs, RevoltRsError};
+use async_std::task;
+use async_trait::async_trait;
+use futures::StreamExt;
+use std::sync::Arc;
+
+/// Shared state handed to every event handler call.
+#[derive(Clone)]
+pub struct Context {
+    pub http: RevoltRs,
+}
+
+impl Context {
+    pub fn cache(&self) -> Option<&Arc<Cache>> {
+        self.http.cache()
+    }
+}
+
+/// Handles gateway events. Every method does nothing by default, so only the
+/// events a bot cares about need to be implemented.
+#[async_trait]
+pub trait EventHandler: Send + Sync {
+    async fn on_ready(
+        &self,
+        _ctx: Context,
+        _users: Vec<user::User>,
+        _servers: Vec<server::Server>,
+        _channels: Vec<channel::Channel>,
+        _members: Vec<server::Member>,
+    ) {
+    }
+    /// Called after the gateway reconnected; events may have been missed.
+    async fn on_reconnect(&self, _ctx: Context) {}
+    /// Called with errors the gateway sends, like a failed authentication.
+    async fn on_error(&self, _ctx: Context, _error: String) {}
+    /// Called with events that could not be read, the gateway keeps running.
+    async fn on_gateway_error(&self, _ctx: Context, _error: RevoltRsError) {}
+    /// Called when an event could not be applied to the cache backend.
+    async fn on_cache_error(&self, _ctx: Context, _error: RevoltRsError) {}
+    async fn on_message(&self, _ctx: Context, _message: message::Message) {}
+    async fn on_message_update(
+        &self,
+        _ctx: Context,
+        _message_id: String,
+        _channel_id: String,
+        _data: message::PartialMessage,
+    ) {
+    }
+    async fn on_message_delete(&self, _ctx: Context, _message_id: String, _channel_id: String) {}
+    async fn on_channel_create(&self, _ctx: Context, _channel: channel::Channel) {}
+    async fn on_channel_update(
+        &self,
+        _ctx: Context,
+        _channel_id: String,
+        _data: channel::PartialChannel,
+        _clear: Option<channel::ChannelField>,
+    ) {
+    }
+    async fn on_channel_delete(&self, _ctx: Context, _channel_id: String) {}
+    async fn on_group_join(&self, _ctx: Context, _channel_id: String, _user_id: String) {}
+    async fn on_group_leave(&self, _ctx: Context, _channel_id: String, _user_id: String) {}
+    async fn on_typing_start(&self, _ctx: Context, _channel_id: String, _user_id: String) {}
+    async fn on_typing_stop(&self, _ctx: Context, _channel_id: String, _user_id: String) {}
+    async fn on_channel_ack(
+        &self,
+        _ctx: Context,
+        _channel_id: String,
+        _user_id: String,
+        _message_id: String,
+    ) {
+    }
+    async fn on_server_update(
+        &self,
+        _ctx: Context,
+        _server_id: String,
+        _data: server::PartialServer,
+        _clear: Option<server::ServerField>,
+    ) {
+    }
+    async fn on_server_delete(&self, _ctx: Context, _server_id: String) {}
+    async fn on_member_join(&self, _ctx: Context, _server_id: String, _user_id: String) {}
+    async fn on_member_leave(&self, _ctx: Context, _server_id: String, _user_id: String) {}
+    async fn on_member_update(
+        &self,
+        _ctx: Context,
+        _member_id: server::MemberId,
+        _data: server::PartialMember,
+        _clear: Option<server::MemberField>,
+    ) {
+    }
+    async fn on_role_update(
+        &self,
+        _ctx: Context,
+        _server_id: String,
+        _role_id: String,
+        _data: server::PartialRole,
+        _clear: Option<server::RoleField>,
+    ) {
+    }
+    async fn on_role_delete(&self, _ctx: Context, _server_id: String, _role_id: String) {}
+    async fn on_user_update(
+        &self,
+        _ctx: Context,
+        _user_id: String,
+        _data: user::PartialUser,
+        _clear: Option<user::UserField>,
+    ) {
+    }
+    async fn on_relationship(
+        &self,
+        _ctx: Context,
+        _user_id: String,
+        _user: user::User,
+        _status: user::RelationshipStatus,
+    ) {
+    }
+}
+
+/// Runs a bot: connects to the gateway and hands every event to an
+/// `EventHandler`, each on its own task.
+pub struct Client {
+    http: RevoltRs,
+}
+
+impl Client {
+    pub fn new(http: RevoltRs) -> Self {
+        Client { http }
+    }
+    /// Dispatches events until the gateway gives up, returning the error that
+    /// stopped it.
+    pub async fn start<H: EventHandler + 'static>(&self, handler: H) -> Result<(), RevoltRsError> {
+        let handler = Arc::new(handler);
+        let ctx = Context {
+            http: self.http.clone(),
+        };
+        let mut gateway = Gateway::connect(&self.http).await?;
+        while let Some(event) = gateway.next().await {
+            let handler = handler.clone();
+            let ctx = ctx.clone();
+            match event {
+                Ok(event) => {
+                    if let Some(cache) = self.http.cache() {
+                        if let Err(error) = cache.update(&event).await {
+                            let handler = handler.clone();
+                            let ctx = ctx.clone();
+                            task::spawn(async move { handler.on_cache_error(ctx, error).await });
+                        }
+                    }
+                    task::spawn(async move { dispatch(&*handler, ctx, event).await });
+                }
+                Err(error @ RevoltRsError::Serde { .. }) => {
+                    task::spawn(async move { handler.on_gateway_error(ctx, error).await });
+                }
+                Err(error) => return Err(error),
+            }
+        }
+        Ok(())
+    }
+}
+
+async fn dispatch<H: EventHandler + ?Sized>(handler: &H, ctx: Context, event: Event) {
+    match event {
+        Event::Error { error } => handler.on_error(ctx, error).await,
+        Event::Ready {
+            users,
+            servers,
+            channels,
+            members,
+        } => {
+            let members = members.unwrap_or_default();
+            handler
+                .on_ready(ctx, users, servers, channels, members)
+                .await
+        }
+        Event::Reconnected => handler.on_reconnect(ctx).await,
+        Event::Message(message) => handler.on_message(ctx, message).await,
+        Event::MessageUpdate { id, channel, data } => {
+            handler.on_message_update(ctx, id, channel, data).await
+        }
+        Event::MessageDelete { id, channel } => handler.on_message_delete(ctx, id, channel).await,
+        Event::ChannelCreate(channel) => handler.on_channel_create(ctx, channel).await,
+        Event::ChannelUpdate { id, data, clear } => {
+            handler.on_channel_update(ctx, id, data, clear).await
+        }
+        Event::ChannelDelete { id } => handler.on_channel_delete(ctx, id).await,
+        Event::ChannelGroupJoin { id, user } => handler.on_group_join(ctx, id, user).await,
+        Event::ChannelGroupLeave { id, user } => handler.on_group_leave(ctx, id, user).await,
+        Event::ChannelStartTyping { id, user } => handler.on_typing_start(ctx, id, user).await,
+        Event::ChannelStopTyping { id, user } => handler.on_typing_stop(ctx, id, user).await,
+        Event::ChannelAck {
+            id,
+            user,
+            message_id,
+        } => handler.on_channel_ack(ctx, id, user, message_id).await,
+        Event::ServerUpdate { id, data, clear } => {
+            handler.on_server_update(ctx, id, data, clear).await
+        }
+        Event::ServerDelete { id } => handler.on_server_delete(ctx, id).await,
+        Event::ServerMemberJoin { id, user } => handler.on_member_join(ctx, id, user).await,
+        Event::ServerMemberLeave { id, user } => handler.on_member_leave(ctx, id, user).await,
+        Event::ServerMemberUpdate { id, data, clear } => {
+            handler.on_member_update(ctx, id, data, clear).await
+        }
+        Event::ServerRoleUpdate {
+            id,
+            role_id,
+            data,
+            clear,
+        } => handler.on_role_update(ctx, id, role_id, data, clear).await,
+        Event::ServerRoleDelete { id, role_id } => handler.on_role_delete(ctx, id, role_id).await,
+        Event::UserUpdate { id, data, clear } => handler.on_user_update(ctx, id, data, clear).await,
+        Event::UserRelationship { id, user, status } => {
+            handler.on_relationship(ctx, id, user, status).await
+        }
+        Event::Authenticated | Event::Pong { .. } | Event::Unknown => {}
+    }
+}
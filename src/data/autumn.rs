@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(tag = "type")]
 pub enum Metadata {
     File,
@@ -10,8 +11,8 @@ pub enum Metadata {
     Video { width: i32, height: i32 },
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-#[serde(tag = "type")]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
 pub enum AttachmentTag {
     Attachments,
     Avatars,
@@ -20,7 +21,19 @@ pub enum AttachmentTag {
     Icons,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+impl AttachmentTag {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AttachmentTag::Attachments => "attachments",
+            AttachmentTag::Avatars => "avatars",
+            AttachmentTag::Backgrounds => "backgrounds",
+            AttachmentTag::Banners => "banners",
+            AttachmentTag::Icons => "icons",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Attachment {
     #[serde(rename = "_id")]
     pub id: String,
@@ -30,3 +43,40 @@ pub struct Attachment {
     pub metadata: Metadata,
     pub content_type: String,
 }
+
+/// A file uploaded to autumn. The file server only answers with the id, the
+/// rest is what was known locally when uploading.
+#[derive(Debug, Clone)]
+pub struct Upload {
+    pub id: String,
+    pub tag: AttachmentTag,
+    pub filename: String,
+    pub size: u64,
+    /// Guessed from the file name.
+    pub content_type: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TagConfig {
+    pub max_size: u64,
+    pub use_ulid: Option<bool>,
+    pub enabled: Option<bool>,
+    pub serve_if_field_present: Option<Vec<String>>,
+    pub restrict_content_type: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AutumnConfig {
+    pub autumn: String,
+    pub tags: HashMap<String, TagConfig>,
+    pub jpeg_quality: Option<u8>,
+}
+
+/// How to resize an image or video thumbnail served by autumn. Unset sides
+/// keep the aspect ratio.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Preview {
+    pub max_side: Option<u32>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+}
@@ -1,8 +1,9 @@
+use crate::data::permissions::ChannelPermission;
 use crate::data::{autumn, message};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct DMChannel {
     #[serde(rename = "_id")]
     pub id: String,
@@ -14,16 +15,15 @@ pub struct DMChannel {
     pub description: Option<String>,
     pub last_message: message::LastMessage,
     pub icon: Option<autumn::Attachment>,
-    pub permissions: Option<i32>,
+    pub permissions: Option<ChannelPermission>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct RolePermissions {
     pub role_permissions: String,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-#[serde(tag = "type")]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum ChannelTypes {
     SavedMessages,
     DirectMessage,
@@ -32,7 +32,7 @@ pub enum ChannelTypes {
     VoiceChannel,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Channel {
     #[serde(rename = "_id")]
     pub id: String,
@@ -46,8 +46,34 @@ pub struct Channel {
     pub last_message: Option<message::LastMessageType>,
     pub user: Option<String>,
     pub icon: Option<autumn::Attachment>,
-    pub default_permissions: Option<i32>,
-    pub role_permissions: Option<HashMap<String, i32>>,
-    pub permissions: Option<i32>,
+    pub default_permissions: Option<ChannelPermission>,
+    pub role_permissions: Option<HashMap<String, ChannelPermission>>,
+    pub permissions: Option<ChannelPermission>,
     pub nonce: Option<String>,
 }
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum ServerChannelType {
+    Text,
+    Voice,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PartialChannel {
+    pub name: Option<String>,
+    pub owner: Option<String>,
+    pub description: Option<String>,
+    pub icon: Option<autumn::Attachment>,
+    pub recipients: Option<Vec<String>>,
+    pub active: Option<bool>,
+    pub last_message: Option<message::LastMessageType>,
+    pub default_permissions: Option<ChannelPermission>,
+    pub role_permissions: Option<HashMap<String, ChannelPermission>>,
+    pub permissions: Option<ChannelPermission>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum ChannelField {
+    Icon,
+    Description,
+}
@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type")]
+pub enum ApiError {
+    LabelMe,
+    AlreadyOnboarded,
+    UsernameTaken,
+    UnknownUser,
+    AlreadyFriends,
+    AlreadySentRequest,
+    Blocked,
+    BlockedByOther,
+    NotFriends,
+    UnknownChannel,
+    UnknownAttachment,
+    UnknownMessage,
+    CannotEditMessage,
+    CannotJoinCall,
+    TooManyAttachments,
+    TooManyReplies,
+    EmptyMessage,
+    CannotRemoveYourself,
+    GroupTooLarge {
+        max: i32,
+    },
+    AlreadyInGroup,
+    NotInGroup,
+    UnknownServer,
+    InvalidRole,
+    Banned,
+    TooManyServers {
+        max: i32,
+    },
+    ReachedMaximumBots,
+    IsBot,
+    BotIsPrivate,
+    MissingPermission {
+        permission: Option<String>,
+    },
+    NotElevated,
+    CannotGiveMissingPermissions,
+    DatabaseError {
+        operation: String,
+        with: String,
+    },
+    InternalError,
+    InvalidOperation,
+    InvalidCredentials,
+    InvalidSession,
+    DuplicateNonce,
+    VosoUnavailable,
+    NotFound,
+    NoEffect,
+    FailedValidation {
+        error: serde_json::Value,
+    },
+    #[serde(other)]
+    Unknown,
+}
@@ -0,0 +1,323 @@
+use crate::data::{channel, message, server, user};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type")]
+pub enum ClientMessage {
+    Authenticate { token: String },
+    BeginTyping { channel: String },
+    EndTyping { channel: String },
+    Ping { data: u64 },
+}
+
+#[allow(clippy::large_enum_variant)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type")]
+pub enum Event {
+    Error {
+        error: String,
+    },
+    Authenticated,
+    Ready {
+        users: Vec<user::User>,
+        servers: Vec<server::Server>,
+        channels: Vec<channel::Channel>,
+        members: Option<Vec<server::Member>>,
+    },
+    Pong {
+        data: u64,
+    },
+    Message(message::Message),
+    MessageUpdate {
+        id: String,
+        channel: String,
+        data: message::PartialMessage,
+    },
+    MessageDelete {
+        id: String,
+        channel: String,
+    },
+    ChannelCreate(channel::Channel),
+    ChannelUpdate {
+        id: String,
+        data: channel::PartialChannel,
+        clear: Option<channel::ChannelField>,
+    },
+    ChannelDelete {
+        id: String,
+    },
+    ChannelGroupJoin {
+        id: String,
+        user: String,
+    },
+    ChannelGroupLeave {
+        id: String,
+        user: String,
+    },
+    ChannelStartTyping {
+        id: String,
+        user: String,
+    },
+    ChannelStopTyping {
+        id: String,
+        user: String,
+    },
+    ChannelAck {
+        id: String,
+        user: String,
+        message_id: String,
+    },
+    ServerUpdate {
+        id: String,
+        data: server::PartialServer,
+        clear: Option<server::ServerField>,
+    },
+    ServerDelete {
+        id: String,
+    },
+    ServerMemberUpdate {
+        id: server::MemberId,
+        data: server::PartialMember,
+        clear: Option<server::MemberField>,
+    },
+    ServerMemberJoin {
+        id: String,
+        user: String,
+    },
+    ServerMemberLeave {
+        id: String,
+        user: String,
+    },
+    ServerRoleUpdate {
+        id: String,
+        role_id: String,
+        data: server::PartialRole,
+        clear: Option<server::RoleField>,
+    },
+    ServerRoleDelete {
+        id: String,
+        role_id: String,
+    },
+    UserUpdate {
+        id: String,
+        data: user::PartialUser,
+        clear: Option<user::UserField>,
+    },
+    UserRelationship {
+        id: String,
+        user: user::User,
+        status: user::RelationshipStatus,
+    },
+    /// Yielded by the gateway after it reconnected on its own. Events sent
+    /// while it was disconnected were missed.
+    Reconnected,
+    #[serde(other)]
+    Unknown,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(json: &str) -> Event {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn ready() {
+        let event = parse(
+            r#"{
+                "type": "Ready",
+                "users": [
+                    {
+                        "_id": "01FD58YK5W7QRV5H3D64KTQYX3",
+                        "username": "bot",
+                        "relationship": "User",
+                        "online": true,
+                        "status": { "text": "hi", "presence": "Online" },
+                        "relations": [{ "_id": "01FDAD6EVAJ4VWHQH0GQCS9S9K", "status": "Friend" }]
+                    }
+                ],
+                "servers": [
+                    {
+                        "_id": "01FDAD7Z1HTRS4S3XX5FT0R8F2",
+                        "owner": "01FDAD6EVAJ4VWHQH0GQCS9S9K",
+                        "name": "server",
+                        "channels": ["01FDAD81Z2GG7Q3X4B2VHQ2Z6J"],
+                        "roles": {
+                            "01FDAD8AXK1AZ7RFBQZ3ZSZB8E": { "name": "mod", "permissions": [63, 1023], "rank": 0 }
+                        },
+                        "default_permissions": [1, 3]
+                    }
+                ],
+                "channels": [
+                    {
+                        "_id": "01FDAD81Z2GG7Q3X4B2VHQ2Z6J",
+                        "server": "01FDAD7Z1HTRS4S3XX5FT0R8F2",
+                        "channel_type": "TextChannel",
+                        "name": "general",
+                        "role_permissions": { "01FDAD8AXK1AZ7RFBQZ3ZSZB8E": 4 }
+                    }
+                ],
+                "members": [
+                    {
+                        "_id": { "server": "01FDAD7Z1HTRS4S3XX5FT0R8F2", "user": "01FD58YK5W7QRV5H3D64KTQYX3" },
+                        "roles": ["01FDAD8AXK1AZ7RFBQZ3ZSZB8E"]
+                    }
+                ]
+            }"#,
+        );
+        let (users, servers, channels, members) = match event {
+            Event::Ready {
+                users,
+                servers,
+                channels,
+                members,
+            } => (users, servers, channels, members.unwrap()),
+            event => panic!("expected Ready, got {:?}", event),
+        };
+        assert!(matches!(
+            users[0].relationship,
+            Some(user::RelationshipStatus::User)
+        ));
+        assert!(matches!(
+            users[0].relations.as_ref().unwrap()[0].status,
+            user::RelationshipStatus::Friend
+        ));
+        let roles = servers[0].roles.as_ref().unwrap();
+        assert_eq!(roles["01FDAD8AXK1AZ7RFBQZ3ZSZB8E"].rank, Some(0));
+        assert!(matches!(
+            channels[0].channel_type,
+            channel::ChannelTypes::TextChannel
+        ));
+        assert_eq!(members[0].roles.as_ref().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn message() {
+        let event = parse(
+            r#"{
+                "type": "Message",
+                "_id": "01FDAD9D8XTF1Q0RKG4XJNAVWE",
+                "channel": "01FDAD81Z2GG7Q3X4B2VHQ2Z6J",
+                "author": "01FDAD6EVAJ4VWHQH0GQCS9S9K",
+                "content": "hello",
+                "mentions": ["01FD58YK5W7QRV5H3D64KTQYX3"]
+            }"#,
+        );
+        match event {
+            Event::Message(message) => {
+                assert_eq!(message.author, "01FDAD6EVAJ4VWHQH0GQCS9S9K");
+                assert!(matches!(
+                    message.content,
+                    Some(message::ContentType::B(content)) if content == "hello"
+                ));
+            }
+            event => panic!("expected Message, got {:?}", event),
+        }
+    }
+
+    #[test]
+    fn member_update_with_clear() {
+        let event = parse(
+            r#"{
+                "type": "ServerMemberUpdate",
+                "id": { "server": "01FDAD7Z1HTRS4S3XX5FT0R8F2", "user": "01FD58YK5W7QRV5H3D64KTQYX3" },
+                "data": { "roles": [] },
+                "clear": "Nickname"
+            }"#,
+        );
+        match event {
+            Event::ServerMemberUpdate { id, data, clear } => {
+                assert_eq!(id.user, "01FD58YK5W7QRV5H3D64KTQYX3");
+                assert_eq!(data.roles.unwrap().len(), 0);
+                assert!(data.nickname.is_none());
+                assert!(matches!(clear, Some(server::MemberField::Nickname)));
+            }
+            event => panic!("expected ServerMemberUpdate, got {:?}", event),
+        }
+        let event = parse(
+            r#"{
+                "type": "ServerMemberUpdate",
+                "id": { "server": "01FDAD7Z1HTRS4S3XX5FT0R8F2", "user": "01FD58YK5W7QRV5H3D64KTQYX3" },
+                "data": { "nickname": "nick" }
+            }"#,
+        );
+        assert!(matches!(
+            event,
+            Event::ServerMemberUpdate { clear: None, .. }
+        ));
+    }
+
+    #[test]
+    fn user_relationship() {
+        let event = parse(
+            r#"{
+                "type": "UserRelationship",
+                "id": "01FD58YK5W7QRV5H3D64KTQYX3",
+                "user": {
+                    "_id": "01FDAD6EVAJ4VWHQH0GQCS9S9K",
+                    "username": "friend",
+                    "relationship": "Incoming"
+                },
+                "status": "Incoming"
+            }"#,
+        );
+        match event {
+            Event::UserRelationship { id, user, status } => {
+                assert_eq!(id, "01FD58YK5W7QRV5H3D64KTQYX3");
+                assert_eq!(user.username, "friend");
+                assert!(matches!(status, user::RelationshipStatus::Incoming));
+            }
+            event => panic!("expected UserRelationship, got {:?}", event),
+        }
+    }
+
+    #[test]
+    fn updates_and_heartbeats() {
+        let event = parse(
+            r#"{
+                "type": "ChannelUpdate",
+                "id": "01FDAD81Z2GG7Q3X4B2VHQ2Z6J",
+                "data": { "name": "renamed", "default_permissions": 3 },
+                "clear": "Icon"
+            }"#,
+        );
+        assert!(matches!(
+            event,
+            Event::ChannelUpdate {
+                clear: Some(channel::ChannelField::Icon),
+                ..
+            }
+        ));
+        assert!(matches!(
+            parse(r#"{ "type": "Pong", "data": 3 }"#),
+            Event::Pong { data: 3 }
+        ));
+        assert!(matches!(
+            parse(r#"{ "type": "Authenticated" }"#),
+            Event::Authenticated
+        ));
+    }
+
+    #[test]
+    fn unknown_events() {
+        assert!(matches!(
+            parse(r#"{ "type": "ServerCreate", "id": "01FDAD7Z1HTRS4S3XX5FT0R8F2" }"#),
+            Event::Unknown
+        ));
+    }
+
+    #[test]
+    fn client_messages() {
+        let ping = serde_json::to_value(&ClientMessage::Ping { data: 1 }).unwrap();
+        assert_eq!(ping, serde_json::json!({ "type": "Ping", "data": 1 }));
+        let typing = ClientMessage::BeginTyping {
+            channel: "01FDAD81Z2GG7Q3X4B2VHQ2Z6J".to_string(),
+        };
+        assert_eq!(
+            serde_json::to_value(&typing).unwrap(),
+            serde_json::json!({ "type": "BeginTyping", "channel": "01FDAD81Z2GG7Q3X4B2VHQ2Z6J" })
+        );
+    }
+}
@@ -1,4 +1,5 @@
-use crate::data::{message, user};
+use crate::data::permissions::{ChannelPermission, ServerPermission};
+use crate::data::{channel, message, server, user};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize)]
@@ -19,7 +20,7 @@ pub struct EditAutumn {
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Permissions {
-    pub permissions: i32,
+    pub permissions: ChannelPermission,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -37,16 +38,124 @@ pub struct SendMessage {
 
 #[derive(Serialize, Deserialize)]
 pub struct SearchMessages {
-    pub query: Option<String>,
+    pub query: String,
     pub limit: Option<i32>,
     pub before: Option<String>,
     pub after: Option<String>,
     pub sort: message::SearchSort,
-    pub nearby: Option<String>,
     pub include_users: Option<bool>,
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct EditMessage {
     pub content: String,
-}
\ No newline at end of file
+}
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RateLimited {
+    pub retry_after: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CreateServer {
+    pub name: String,
+    pub description: Option<String>,
+    pub nonce: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct EditServer {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub icon: Option<String>,
+    pub banner: Option<String>,
+    pub categories: Option<Vec<server::Category>>,
+    pub system_messages: Option<server::SystemMessages>,
+    pub remove: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CreateChannel {
+    #[serde(rename = "type")]
+    pub channel_type: channel::ServerChannelType,
+    pub name: String,
+    pub description: Option<String>,
+    pub nonce: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct EditMember {
+    pub nickname: Option<String>,
+    pub avatar: Option<String>,
+    pub roles: Option<Vec<String>>,
+    pub remove: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct BanUser {
+    pub reason: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CreateRole {
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct EditRole {
+    pub name: Option<String>,
+    pub colour: Option<String>,
+    pub hoist: Option<bool>,
+    pub rank: Option<i32>,
+    pub remove: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ServerPermissions {
+    pub server: ServerPermission,
+    pub channel: ChannelPermission,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SetServerPermissions {
+    pub permissions: ServerPermissions,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct UploadedFile {
+    pub id: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct FetchMessages {
+    pub limit: Option<i32>,
+    pub before: Option<String>,
+    pub after: Option<String>,
+    pub sort: Option<message::SearchSort>,
+    pub nearby: Option<String>,
+    pub include_users: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum FetchedMessages {
+    Messages(Vec<message::Message>),
+    WithUsers(message::Messages),
+}
+
+impl From<FetchedMessages> for message::Messages {
+    fn from(fetched: FetchedMessages) -> Self {
+        match fetched {
+            FetchedMessages::Messages(messages) => message::Messages {
+                messages: Some(messages),
+                users: None,
+                members: None,
+            },
+            FetchedMessages::WithUsers(messages) => messages,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct BulkDeleteMessages {
+    pub ids: Vec<String>,
+}
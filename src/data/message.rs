@@ -1,7 +1,7 @@
 use crate::data::{autumn, user, server};
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct LastMessage {
     #[serde(rename = "_id")]
     pub id: String,
@@ -9,14 +9,14 @@ pub struct LastMessage {
     pub short: String,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(untagged)]
 pub enum LastMessageType {
     A(LastMessage),
     B(String),
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct MessageContent {
     #[serde(rename = "type")]
     pub message_type: String,
@@ -26,20 +26,20 @@ pub struct MessageContent {
     pub content: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(untagged)]
 pub enum ContentType {
     A(MessageContent),
     B(String),
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct MessageEdited {
     #[serde(rename = "$date")]
     pub date: String,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(tag = "type")]
 pub enum EmbedTypes {
     None,
@@ -47,7 +47,7 @@ pub enum EmbedTypes {
     Image,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(tag = "type")]
 pub enum SpecialEmbedTypes {
     None,
@@ -58,7 +58,7 @@ pub enum SpecialEmbedTypes {
     Bandcamp,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum SEContentTypes {
     Channel,
     Clip,
@@ -67,13 +67,13 @@ pub enum SEContentTypes {
     Track,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum SEContentType {
     A(SEContentTypes),
     B(String),
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct SpecialEmbed {
     #[serde(rename = "type")]
     pub se_type: SpecialEmbedTypes,
@@ -81,14 +81,14 @@ pub struct SpecialEmbed {
     pub id: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(tag = "type")]
 pub enum ImageSize {
     Large,
     Preview,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Image {
     pub url: String,
     pub width: i32,
@@ -96,14 +96,14 @@ pub struct Image {
     pub size: ImageSize,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Video {
     pub url: String,
     pub width: i32,
     pub height: i32,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Embed {
     #[serde(rename = "type")]
     pub embed_type: SpecialEmbedTypes,
@@ -121,7 +121,7 @@ pub struct Embed {
     pub color: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Message {
     #[serde(rename = "_id")]
     pub id: String,
@@ -136,21 +136,27 @@ pub struct Message {
     pub replies: Option<Vec<String>>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PartialMessage {
+    pub content: Option<ContentType>,
+    pub edited: Option<MessageEdited>,
+    pub embeds: Option<Vec<Embed>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Messages {
     pub messages: Option<Vec<Message>>,
     pub users: Option<Vec<user::User>>,
     pub members: Option<Vec<server::Member>>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Reply {
     pub id: String,
     pub mention: bool,
 }
 
-#[derive(Serialize, Deserialize)]
-#[serde(tag = "type")]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
 pub enum SearchSort {
     Latest,
     Oldest,
@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CaptchaFeature {
+    pub enabled: bool,
+    pub key: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Feature {
+    pub enabled: bool,
+    pub url: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct VosoFeature {
+    pub enabled: bool,
+    pub url: String,
+    pub ws: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Features {
+    pub captcha: CaptchaFeature,
+    pub email: bool,
+    pub invite_only: bool,
+    pub autumn: Feature,
+    pub january: Feature,
+    pub voso: VosoFeature,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NodeInfo {
+    pub revolt: String,
+    pub features: Features,
+    pub ws: String,
+    pub app: String,
+    pub vapid: String,
+}
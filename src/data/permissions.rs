@@ -0,0 +1,65 @@
+use bitflags::bitflags;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+bitflags! {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+    pub struct ChannelPermission: u32 {
+        const VIEW = 1 << 0;
+        const SEND_MESSAGE = 1 << 1;
+        const MANAGE_MESSAGES = 1 << 2;
+        const MANAGE_CHANNEL = 1 << 3;
+        const VOICE_CALL = 1 << 4;
+        const INVITE_OTHERS = 1 << 5;
+        const EMBED_LINKS = 1 << 6;
+        const UPLOAD_FILES = 1 << 7;
+        const MASQUERADE = 1 << 8;
+    }
+}
+
+bitflags! {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+    pub struct ServerPermission: u32 {
+        const VIEW = 1 << 0;
+        const MANAGE_ROLES = 1 << 1;
+        const MANAGE_CHANNELS = 1 << 2;
+        const MANAGE_SERVER = 1 << 3;
+        const KICK_MEMBERS = 1 << 4;
+        const BAN_MEMBERS = 1 << 5;
+        const CHANGE_NICKNAME = 1 << 12;
+        const MANAGE_NICKNAMES = 1 << 13;
+        const CHANGE_AVATAR = 1 << 14;
+        const REMOVE_AVATARS = 1 << 15;
+    }
+}
+
+bitflags! {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+    pub struct UserPermission: u32 {
+        const ACCESS = 1 << 0;
+        const VIEW_PROFILE = 1 << 1;
+        const SEND_MESSAGE = 1 << 2;
+        const INVITE = 1 << 3;
+    }
+}
+
+// The API sends permissions as plain numbers. Bits this crate doesn't know
+// about yet are kept, so they survive being sent back to the API.
+macro_rules! impl_serde_bits {
+    ($permission:ty) => {
+        impl Serialize for $permission {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                self.bits().serialize(serializer)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $permission {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                u32::deserialize(deserializer).map(<$permission>::from_bits_retain)
+            }
+        }
+    };
+}
+
+impl_serde_bits!(ChannelPermission);
+impl_serde_bits!(ServerPermission);
+impl_serde_bits!(UserPermission);
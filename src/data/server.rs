@@ -1,15 +1,16 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use crate::data::autumn;
+use crate::data::permissions::{ChannelPermission, ServerPermission};
+use crate::data::{autumn, user};
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Category {
     pub id: String,
     pub title: String,
     pub channels: Vec<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct SystemMessages {
     pub user_joined: Option<String>,
     pub user_left: Option<String>,
@@ -17,16 +18,22 @@ pub struct SystemMessages {
     pub user_banned: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Role {
     pub name: String,
-    pub permissions: (i32, i32),
+    pub permissions: (ServerPermission, ChannelPermission),
     pub colour: Option<String>,
     pub hoist: Option<bool>,
     pub rank: Option<i32>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NewRole {
+    pub id: String,
+    pub permissions: (ServerPermission, ChannelPermission),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Server {
     #[serde(rename = "_id")]
     pub id: String,
@@ -38,22 +45,96 @@ pub struct Server {
     pub categories: Option<Vec<Category>>,
     pub system_messages: Option<SystemMessages>,
     pub roles: Option<HashMap<String, Role>>,
-    pub default_permissions: (i32, i32),
+    pub default_permissions: (ServerPermission, ChannelPermission),
     pub icon: Option<autumn::Attachment>,
     pub banner: Option<autumn::Attachment>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct MemberId {
     pub server: String,
     pub user: String,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Member {
     #[serde(rename = "_id")]
     pub id: MemberId,
     pub nickname: Option<String>,
     pub avatar: Option<autumn::Attachment>,
     pub roles: Option<Vec<String>>,
-}
\ No newline at end of file
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Members {
+    pub members: Vec<Member>,
+    pub users: Vec<user::User>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Ban {
+    #[serde(rename = "_id")]
+    pub id: MemberId,
+    pub reason: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BannedUser {
+    #[serde(rename = "_id")]
+    pub id: String,
+    pub username: String,
+    pub avatar: Option<autumn::Attachment>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Bans {
+    pub users: Vec<BannedUser>,
+    pub bans: Vec<Ban>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PartialServer {
+    pub owner: Option<String>,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub channels: Option<Vec<String>>,
+    pub categories: Option<Vec<Category>>,
+    pub system_messages: Option<SystemMessages>,
+    pub default_permissions: Option<(ServerPermission, ChannelPermission)>,
+    pub icon: Option<autumn::Attachment>,
+    pub banner: Option<autumn::Attachment>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum ServerField {
+    Icon,
+    Banner,
+    Description,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PartialMember {
+    pub nickname: Option<String>,
+    pub avatar: Option<autumn::Attachment>,
+    pub roles: Option<Vec<String>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum MemberField {
+    Nickname,
+    Avatar,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PartialRole {
+    pub name: Option<String>,
+    pub permissions: Option<(ServerPermission, ChannelPermission)>,
+    pub colour: Option<String>,
+    pub hoist: Option<bool>,
+    pub rank: Option<i32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum RoleField {
+    Colour,
+}
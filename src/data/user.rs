@@ -1,8 +1,7 @@
 use crate::data::autumn;
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Debug)]
-#[serde(tag = "type")]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum RelationshipStatus {
     Blocked,
     BlockedOther,
@@ -13,14 +12,14 @@ pub enum RelationshipStatus {
     User,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Relationship {
     pub status: RelationshipStatus,
     #[serde(rename = "_id")]
     pub id: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum StatusPresence {
     Busy,
     Idle,
@@ -28,13 +27,13 @@ pub enum StatusPresence {
     Online,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Status {
     pub text: Option<String>,
     pub presence: Option<StatusPresence>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct User {
     #[serde(rename = "_id")]
     pub id: String,
@@ -49,15 +48,34 @@ pub struct User {
     pub bot: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(untagged)]
 pub enum ProfileTypes {
     A(String),
     B(autumn::Attachment),
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Profile {
     pub content: Option<String>,
     pub background: Option<ProfileTypes>,
 }
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PartialUser {
+    pub username: Option<String>,
+    pub avatar: Option<autumn::Attachment>,
+    pub badges: Option<i32>,
+    pub status: Option<Status>,
+    pub profile: Option<Profile>,
+    pub online: Option<bool>,
+    pub flags: Option<i32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum UserField {
+    ProfileContent,
+    ProfileBackground,
+    StatusText,
+    Avatar,
+}
@@ -0,0 +1,224 @@
+use crate::data::autumn::{Attachment, AttachmentTag, AutumnConfig, Preview, Upload};
+use crate::data::internal;
+use crate::{RevoltRs, RevoltRsError};
+use async_std::fs::File;
+use futures::{stream, AsyncRead, AsyncReadExt, Stream, StreamExt};
+use reqwest::multipart::{Form, Part};
+use reqwest::Body;
+use std::convert::TryFrom;
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+
+const CHUNK_SIZE: usize = 64 * 1024;
+
+impl RevoltRs {
+    /// The configuration of the file server (autumn), fetched once and kept.
+    pub async fn fetch_autumn_config(&self) -> Result<Arc<AutumnConfig>, RevoltRsError> {
+        if let Some(config) = self.config.autumn.read().unwrap().clone() {
+            return Ok(config);
+        }
+        let url = format!("{0}/", self.autumn().await?);
+        let data = self.get(url).send().await?.text().await?;
+        let config: Arc<AutumnConfig> = Arc::new(serde_json::from_str(&data)?);
+        *self.config.autumn.write().unwrap() = Some(config.clone());
+        Ok(config)
+    }
+    /// Uploads a file to the file server. The id of the returned upload can
+    /// be passed to `send_message`, `edit_user`, `edit_channel` and
+    /// `edit_server`.
+    pub async fn upload(
+        &self,
+        tag: AttachmentTag,
+        filename: &str,
+        data: Vec<u8>,
+    ) -> Result<Upload, RevoltRsError> {
+        let size = data.len() as u64;
+        self.upload_part(tag, filename, size, Part::bytes(data))
+            .await
+    }
+    pub async fn upload_file<P: AsRef<Path>>(
+        &self,
+        tag: AttachmentTag,
+        path: P,
+    ) -> Result<Upload, RevoltRsError> {
+        let path = path.as_ref();
+        let filename = path
+            .file_name()
+            .map(|filename| filename.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "file".to_string());
+        let file = File::open(path).await?;
+        let size = file.metadata().await?.len();
+        self.upload_reader(tag, &filename, file, size).await
+    }
+    /// Uploads `size` bytes read from `reader` without buffering the whole
+    /// file in memory.
+    pub async fn upload_reader<R>(
+        &self,
+        tag: AttachmentTag,
+        filename: &str,
+        reader: R,
+        size: u64,
+    ) -> Result<Upload, RevoltRsError>
+    where
+        R: AsyncRead + Send + Sync + Unpin + 'static,
+    {
+        let body = Body::wrap_stream(chunks(reader));
+        let part = Part::stream_with_length(body, size);
+        self.upload_part(tag, filename, size, part).await
+    }
+    async fn upload_part(
+        &self,
+        tag: AttachmentTag,
+        filename: &str,
+        size: u64,
+        part: Part,
+    ) -> Result<Upload, RevoltRsError> {
+        let autumn = self.autumn().await?;
+        let config = self.fetch_autumn_config().await?;
+        let max_size = match config.tags.get(tag.as_str()) {
+            Some(tag_config) if tag_config.enabled != Some(false) => tag_config.max_size,
+            _ => return Err(RevoltRsError::UnknownTag { tag }),
+        };
+        // Attachment sizes are 32 bit, whatever the tag allows.
+        if size > max_size || i32::try_from(size).is_err() {
+            let max_size = max_size.min(i32::MAX as u64);
+            return Err(RevoltRsError::FileTooLarge { size, max_size });
+        }
+        let content_type = content_type(filename);
+        let part = part
+            .file_name(filename.to_string())
+            .mime_str(content_type)?;
+        let url = format!("{0}/{1}", autumn, tag.as_str());
+        let data = self
+            .post(url)
+            .multipart(Form::new().part("file", part))
+            .send()
+            .await?
+            .text()
+            .await?;
+        let uploaded: internal::UploadedFile = serde_json::from_str(&data)?;
+        Ok(Upload {
+            id: uploaded.id,
+            tag,
+            filename: filename.to_string(),
+            size,
+            content_type: content_type.to_string(),
+        })
+    }
+    /// Where an attachment is served, once the node has been discovered.
+    pub fn attachment_url(&self, attachment: &Attachment) -> Option<String> {
+        Some(file_url(&self.autumn_url()?, attachment))
+    }
+    /// Like `attachment_url`, but served as a download rather than inline.
+    pub fn attachment_download_url(&self, attachment: &Attachment) -> Option<String> {
+        Some(download_url(&self.autumn_url()?, attachment))
+    }
+    /// Where a resized preview of an image or video is served.
+    pub fn attachment_preview_url(
+        &self,
+        attachment: &Attachment,
+        preview: &Preview,
+    ) -> Option<String> {
+        let url = self.attachment_url(attachment)?;
+        let parameters: Vec<String> = [
+            ("max_side", preview.max_side),
+            ("width", preview.width),
+            ("height", preview.height),
+        ]
+        .iter()
+        .filter_map(|(name, value)| value.map(|value| format!("{0}={1}", name, value)))
+        .collect();
+        if parameters.is_empty() {
+            return Some(url);
+        }
+        Some(format!("{0}?{1}", url, parameters.join("&")))
+    }
+    /// Downloads an attachment exactly as it is stored, through the download
+    /// route.
+    pub async fn download(&self, attachment: &Attachment) -> Result<Vec<u8>, RevoltRsError> {
+        let url = download_url(&self.autumn().await?, attachment);
+        let data = self.get(url).send().await?.bytes().await?.to_vec();
+        Ok(data)
+    }
+    /// Downloads an attachment in chunks as they arrive, for files too large
+    /// to hold in memory.
+    pub async fn download_stream(
+        &self,
+        attachment: &Attachment,
+    ) -> Result<impl Stream<Item = Result<Vec<u8>, RevoltRsError>>, RevoltRsError> {
+        let url = download_url(&self.autumn().await?, attachment);
+        let response = self.get(url).send().await?;
+        Ok(response.bytes_stream().map(|chunk| Ok(chunk?.to_vec())))
+    }
+    pub(crate) async fn autumn(&self) -> Result<String, RevoltRsError> {
+        let node = self.node().await?;
+        if !node.features.autumn.enabled {
+            return Err(RevoltRsError::AutumnUnavailable);
+        }
+        Ok(node.features.autumn.url.trim_end_matches('/').to_string())
+    }
+}
+
+fn file_url(autumn: &str, attachment: &Attachment) -> String {
+    format!(
+        "{0}/{1}/{2}",
+        autumn.trim_end_matches('/'),
+        attachment.tag.as_str(),
+        attachment.id
+    )
+}
+
+fn download_url(autumn: &str, attachment: &Attachment) -> String {
+    format!(
+        "{0}/{1}/download/{2}",
+        autumn.trim_end_matches('/'),
+        attachment.tag.as_str(),
+        attachment.id
+    )
+}
+
+fn content_type(filename: &str) -> &'static str {
+    let extension = filename
+        .rsplit_once('.')
+        .map(|(_, extension)| extension.to_ascii_lowercase());
+    match extension.as_deref() {
+        Some("png") => "image/png",
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        Some("svg") => "image/svg+xml",
+        Some("mp4") => "video/mp4",
+        Some("webm") => "video/webm",
+        Some("mov") => "video/quicktime",
+        Some("mp3") => "audio/mpeg",
+        Some("ogg") => "audio/ogg",
+        Some("wav") => "audio/wav",
+        Some("flac") => "audio/flac",
+        Some("txt") => "text/plain",
+        Some("md") => "text/markdown",
+        Some("json") => "application/json",
+        Some("pdf") => "application/pdf",
+        Some("zip") => "application/zip",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Reads `reader` to the end in chunks, stopping after the first error.
+fn chunks<R>(reader: R) -> impl futures::Stream<Item = io::Result<Vec<u8>>> + Send + Sync
+where
+    R: AsyncRead + Send + Sync + Unpin + 'static,
+{
+    stream::unfold(Some(reader), |reader| async move {
+        let mut reader = reader?;
+        let mut buffer = vec![0; CHUNK_SIZE];
+        match reader.read(&mut buffer).await {
+            Ok(0) => None,
+            Ok(read) => {
+                buffer.truncate(read);
+                Some((Ok(buffer), Some(reader)))
+            }
+            Err(error) => Some((Err(error), None)),
+        }
+    })
+}
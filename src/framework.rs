@@ -0,0 +1,340 @@
+use crate::data::permissions::{ChannelPermission, ServerPermission};
+use crate::data::{channel, message, server, user};
+use crate::{Context, EventHandler, RevoltRs, RevoltRsError};
+use async_trait::async_trait;
+use futures::future::BoxFuture;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use thiserror::Error;
+
+mod args;
+mod checks;
+
+pub use self::args::{Args, Argument, ArgumentError, ChannelMention, UserMention};
+pub use self::checks::{Bucket, Cooldown};
+use self::checks::{Checks, Scope};
+
+type CommandFn =
+    dyn Fn(CommandContext, Args) -> BoxFuture<'static, Result<(), CommandError>> + Send + Sync;
+type ErrorFn = dyn Fn(CommandContext, CommandError) -> BoxFuture<'static, ()> + Send + Sync;
+
+/// Why a command did not run or failed. Failed checks are reported with their
+/// own variant, so bots can tell the user what went wrong.
+#[derive(Error, Debug)]
+pub enum CommandError {
+    #[error("invalid arguments: {source}")]
+    Argument {
+        #[from]
+        source: ArgumentError,
+    },
+    #[error("a request to Revolt failed")]
+    Revolt {
+        #[from]
+        source: RevoltRsError,
+    },
+    #[error("missing permissions: {server:?} {channel:?}")]
+    MissingPermissions {
+        server: ServerPermission,
+        channel: ChannelPermission,
+    },
+    #[error("the bot is missing permissions: {server:?} {channel:?}")]
+    BotMissingPermissions {
+        server: ServerPermission,
+        channel: ChannelPermission,
+    },
+    #[error("only the owners of the bot can use this command")]
+    OwnerOnly,
+    #[error("this command can only be used outside of servers")]
+    DirectOnly,
+    #[error("this command can only be used in servers")]
+    ServerOnly,
+    #[error("this command is on cooldown for {remaining:?}")]
+    Cooldown { remaining: Duration },
+}
+
+/// A command invocation: the message that triggered it and where it came from.
+#[derive(Clone)]
+pub struct CommandContext {
+    pub ctx: Context,
+    pub message: message::Message,
+    /// The server the message was sent in, `None` in DMs and groups.
+    pub server_id: Option<String>,
+    pub prefix: String,
+    /// The name of the command, even when it was invoked with an alias.
+    pub command: String,
+}
+
+impl CommandContext {
+    pub fn http(&self) -> &RevoltRs {
+        &self.ctx.http
+    }
+    /// Sends a message to the channel the command was invoked in.
+    pub async fn say(&self, content: &str) -> Result<message::Message, RevoltRsError> {
+        self.ctx
+            .http
+            .send_message(&self.message.channel, content, None, None)
+            .await
+    }
+    /// Sends a message replying to the message that invoked the command.
+    pub async fn reply(&self, content: &str) -> Result<message::Message, RevoltRsError> {
+        let reply = message::Reply {
+            id: self.message.id.clone(),
+            mention: false,
+        };
+        self.ctx
+            .http
+            .send_message(&self.message.channel, content, None, Some(reply))
+            .await
+    }
+}
+
+pub struct Command {
+    name: String,
+    aliases: Vec<String>,
+    handler: Box<CommandFn>,
+    checks: Checks,
+}
+
+impl Command {
+    pub fn new<F, Fut>(name: &str, handler: F) -> Self
+    where
+        F: Fn(CommandContext, Args) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), CommandError>> + Send + 'static,
+    {
+        Command {
+            name: name.to_string(),
+            aliases: Vec::new(),
+            handler: Box::new(move |ctx, args| Box::pin(handler(ctx, args))),
+            checks: Checks::new(),
+        }
+    }
+    pub fn alias(mut self, alias: &str) -> Self {
+        self.aliases.push(alias.to_string());
+        self
+    }
+    /// Permissions the caller needs in the server and channel.
+    pub fn required_permissions(
+        mut self,
+        server: ServerPermission,
+        channel: ChannelPermission,
+    ) -> Self {
+        self.checks.permissions = (server, channel);
+        self
+    }
+    /// Permissions the bot needs in the server and channel.
+    pub fn bot_permissions(mut self, server: ServerPermission, channel: ChannelPermission) -> Self {
+        self.checks.bot_permissions = (server, channel);
+        self
+    }
+    /// Only lets the owners set with `Framework::owner` use the command.
+    pub fn owners_only(mut self) -> Self {
+        self.checks.owners_only = true;
+        self
+    }
+    /// Only allows the command in direct messages, groups and saved messages.
+    pub fn direct_only(mut self) -> Self {
+        self.checks.scope = Scope::Direct;
+        self
+    }
+    pub fn server_only(mut self) -> Self {
+        self.checks.scope = Scope::Server;
+        self
+    }
+    pub fn cooldown(mut self, cooldown: Cooldown) -> Self {
+        self.checks.cooldowns.push(cooldown);
+        self
+    }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+/// Runs commands from messages starting with a prefix.
+///
+/// The framework is an `EventHandler`, so it can be given to a `Client`
+/// directly. Bots with their own handler can call `dispatch` from
+/// `on_message` instead. Messages sent by the bot itself are ignored once
+/// the framework has seen the `Ready` event.
+pub struct Framework {
+    prefix: String,
+    prefixes: RwLock<HashMap<String, String>>,
+    commands: HashMap<String, Arc<Command>>,
+    on_error: Option<Box<ErrorFn>>,
+    owners: Vec<String>,
+    user_id: RwLock<Option<String>>,
+}
+
+impl Framework {
+    pub fn new(prefix: &str) -> Self {
+        Framework {
+            prefix: prefix.to_string(),
+            prefixes: RwLock::default(),
+            commands: HashMap::new(),
+            on_error: None,
+            owners: Vec::new(),
+            user_id: RwLock::default(),
+        }
+    }
+    /// Registers a command under its name and aliases, replacing any command
+    /// registered under the same names before.
+    pub fn command(mut self, command: Command) -> Self {
+        let command = Arc::new(command);
+        for name in std::iter::once(&command.name).chain(&command.aliases) {
+            self.commands.insert(name.clone(), command.clone());
+        }
+        self
+    }
+    /// Adds a user allowed to run commands marked `owners_only`.
+    pub fn owner(mut self, user_id: &str) -> Self {
+        self.owners.push(user_id.to_string());
+        self
+    }
+    /// Called with the errors commands return and the checks that failed.
+    pub fn on_error<F, Fut>(mut self, on_error: F) -> Self
+    where
+        F: Fn(CommandContext, CommandError) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.on_error = Some(Box::new(move |ctx, error| Box::pin(on_error(ctx, error))));
+        self
+    }
+    /// Uses `prefix` instead of the default prefix in a server.
+    pub fn set_prefix(&self, server_id: &str, prefix: &str) {
+        self.prefixes
+            .write()
+            .unwrap()
+            .insert(server_id.to_string(), prefix.to_string());
+    }
+    pub fn remove_prefix(&self, server_id: &str) {
+        self.prefixes.write().unwrap().remove(server_id);
+    }
+    /// The prefix used in a server, or in DMs and groups for `None`.
+    pub fn prefix(&self, server_id: Option<&str>) -> String {
+        server_id
+            .and_then(|server_id| self.prefixes.read().unwrap().get(server_id).cloned())
+            .unwrap_or_else(|| self.prefix.clone())
+    }
+    /// Whether a message starts with any prefix in use, so the channel is only
+    /// looked up for messages that can be commands.
+    fn has_prefix(&self, content: &str) -> bool {
+        content.starts_with(&self.prefix)
+            || self
+                .prefixes
+                .read()
+                .unwrap()
+                .values()
+                .any(|prefix| content.starts_with(prefix.as_str()))
+    }
+    pub fn commands(&self) -> impl Iterator<Item = &Command> {
+        self.commands
+            .iter()
+            .filter(|(name, command)| **name == command.name)
+            .map(|(_, command)| &**command)
+    }
+    /// Runs the command a message invokes, if any.
+    pub async fn dispatch(&self, ctx: Context, message: message::Message) {
+        let content = match &message.content {
+            Some(message::ContentType::B(content)) => content.clone(),
+            _ => return,
+        };
+        if self.user_id.read().unwrap().as_ref() == Some(&message.author) {
+            return;
+        }
+        if !self.has_prefix(&content) {
+            return;
+        }
+        // Answered from the cache when the client has one.
+        let channel = ctx.http.fetch_channel(&message.channel).await;
+        let server_id = channel
+            .as_ref()
+            .ok()
+            .and_then(|channel| channel.server.clone());
+        let prefix = self.prefix(server_id.as_deref());
+        let invocation = match content.strip_prefix(&prefix) {
+            Some(invocation) => invocation.trim_start(),
+            None => return,
+        };
+        let (name, args) = invocation
+            .split_once(char::is_whitespace)
+            .unwrap_or((invocation, ""));
+        let command = match self.commands.get(name) {
+            Some(command) => command.clone(),
+            None => return,
+        };
+        let ctx = CommandContext {
+            ctx,
+            message,
+            server_id,
+            prefix,
+            command: command.name.clone(),
+        };
+        let result = match self.checks(&command, &ctx, channel).await {
+            Ok(()) => (command.handler)(ctx.clone(), Args::new(args)).await,
+            Err(error) => Err(error),
+        };
+        if let Err(error) = result {
+            if let Some(on_error) = &self.on_error {
+                on_error(ctx, error).await;
+            }
+        }
+    }
+}
+
+impl Framework {
+    /// Runs the checks of a command. When the channel could not be fetched,
+    /// whether the message came from a server is unknown, so commands with
+    /// scope or permission checks fail with that error instead of guessing.
+    async fn checks(
+        &self,
+        command: &Command,
+        ctx: &CommandContext,
+        channel: Result<channel::Channel, RevoltRsError>,
+    ) -> Result<(), CommandError> {
+        let channel = match channel {
+            Ok(channel) => Some(channel),
+            Err(error) if command.checks.needs_channel() => return Err(error.into()),
+            Err(_) => None,
+        };
+        let user_id = self.user_id.read().unwrap().clone();
+        let user_id = match user_id {
+            Some(user_id) => Some(user_id),
+            None if command.checks.needs_bot() => {
+                let user_id = ctx.http().fetch_user("@me").await?.id;
+                *self.user_id.write().unwrap() = Some(user_id.clone());
+                Some(user_id)
+            }
+            None => None,
+        };
+        command
+            .checks
+            .run(ctx, channel.as_ref(), &self.owners, user_id.as_deref())
+            .await
+    }
+}
+
+#[async_trait]
+impl EventHandler for Framework {
+    async fn on_ready(
+        &self,
+        _ctx: Context,
+        users: Vec<user::User>,
+        _servers: Vec<server::Server>,
+        _channels: Vec<channel::Channel>,
+        _members: Vec<server::Member>,
+    ) {
+        let user = users
+            .into_iter()
+            .find(|user| matches!(user.relationship, Some(user::RelationshipStatus::User)));
+        if let Some(user) = user {
+            *self.user_id.write().unwrap() = Some(user.id);
+        }
+    }
+    async fn on_message(&self, ctx: Context, message: message::Message) {
+        self.dispatch(ctx, message).await;
+    }
+}
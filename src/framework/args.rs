@@ -0,0 +1,278 @@
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ArgumentError {
+    #[error("a required argument is missing")]
+    Missing,
+    #[error("{argument:?} is not {expected}")]
+    Invalid {
+        argument: String,
+        expected: &'static str,
+    },
+    #[error("a quoted argument is missing its closing quote")]
+    UnclosedQuote,
+}
+
+/// A value that can be read from a single command argument.
+pub trait Argument: Sized {
+    fn from_argument(argument: &str) -> Result<Self, ArgumentError>;
+}
+
+/// The arguments following a command name, read one at a time.
+///
+/// Arguments are separated by whitespace. An argument wrapped in double
+/// quotes may contain whitespace, and `\"` and `\\` escape a quote and a
+/// backslash inside it.
+#[derive(Debug, Clone)]
+pub struct Args {
+    message: String,
+    offset: usize,
+}
+
+impl Args {
+    pub fn new(message: &str) -> Self {
+        Args {
+            message: message.to_string(),
+            offset: 0,
+        }
+    }
+    /// The arguments that have not been read yet.
+    pub fn remaining(&self) -> &str {
+        self.message[self.offset..].trim()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.remaining().is_empty()
+    }
+    /// Reads the next argument. Nothing is consumed when it fails to parse.
+    pub fn single<T: Argument>(&mut self) -> Result<T, ArgumentError> {
+        let (argument, end) = self.peek()?.ok_or(ArgumentError::Missing)?;
+        let value = T::from_argument(&argument)?;
+        self.offset = end;
+        Ok(value)
+    }
+    /// Reads the next argument if there is one left.
+    pub fn optional<T: Argument>(&mut self) -> Result<Option<T>, ArgumentError> {
+        if self.is_empty() {
+            return Ok(None);
+        }
+        self.single().map(Some)
+    }
+    /// Reads everything that is left as one argument, as it was written.
+    pub fn rest(&mut self) -> Result<String, ArgumentError> {
+        let rest = self.remaining().to_string();
+        if rest.is_empty() {
+            return Err(ArgumentError::Missing);
+        }
+        self.offset = self.message.len();
+        Ok(rest)
+    }
+    /// The next argument with its quotes and escapes removed, and the offset
+    /// right after it.
+    fn peek(&self) -> Result<Option<(String, usize)>, ArgumentError> {
+        let start = self.message.len() - self.message[self.offset..].trim_start().len();
+        let mut chars = self.message[start..].char_indices();
+        let mut argument = String::new();
+        let first = match chars.next() {
+            Some((_, first)) => first,
+            None => return Ok(None),
+        };
+        if first == '"' {
+            while let Some((index, c)) = chars.next() {
+                match c {
+                    '"' => return Ok(Some((argument, start + index + 1))),
+                    '\\' => match chars.next() {
+                        Some((_, c @ '"')) | Some((_, c @ '\\')) => argument.push(c),
+                        Some((_, c)) => {
+                            argument.push('\\');
+                            argument.push(c);
+                        }
+                        None => argument.push('\\'),
+                    },
+                    c => argument.push(c),
+                }
+            }
+            return Err(ArgumentError::UnclosedQuote);
+        }
+        argument.push(first);
+        for (index, c) in chars {
+            if c.is_whitespace() {
+                return Ok(Some((argument, start + index)));
+            }
+            argument.push(c);
+        }
+        Ok(Some((argument, self.message.len())))
+    }
+}
+
+impl Argument for String {
+    fn from_argument(argument: &str) -> Result<Self, ArgumentError> {
+        Ok(argument.to_string())
+    }
+}
+
+macro_rules! integer_argument {
+    ($($integer:ty),*) => {
+        $(
+            impl Argument for $integer {
+                fn from_argument(argument: &str) -> Result<Self, ArgumentError> {
+                    argument.parse().map_err(|_| ArgumentError::Invalid {
+                        argument: argument.to_string(),
+                        expected: "an integer",
+                    })
+                }
+            }
+        )*
+    };
+}
+
+integer_argument!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+/// A user, written as a mention (`<@ULID>`) or as a bare id.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UserMention(pub String);
+
+/// A channel, written as a mention (`<#ULID>`) or as a bare id.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChannelMention(pub String);
+
+impl Argument for UserMention {
+    fn from_argument(argument: &str) -> Result<Self, ArgumentError> {
+        mention(argument, '@', "a user mention").map(UserMention)
+    }
+}
+
+impl Argument for ChannelMention {
+    fn from_argument(argument: &str) -> Result<Self, ArgumentError> {
+        mention(argument, '#', "a channel mention").map(ChannelMention)
+    }
+}
+
+fn mention(argument: &str, sigil: char, expected: &'static str) -> Result<String, ArgumentError> {
+    let id = argument
+        .strip_prefix('<')
+        .and_then(|id| id.strip_prefix(sigil))
+        .and_then(|id| id.strip_suffix('>'))
+        .unwrap_or(argument);
+    if is_ulid(id) {
+        Ok(id.to_string())
+    } else {
+        Err(ArgumentError::Invalid {
+            argument: argument.to_string(),
+            expected,
+        })
+    }
+}
+
+fn is_ulid(id: &str) -> bool {
+    id.len() == 26
+        && id
+            .chars()
+            .all(|c| c.is_ascii_digit() || c.is_ascii_uppercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ID: &str = "01FD58YK5W7QRV5H3D64KTQYX3";
+
+    #[test]
+    fn splits_on_whitespace() {
+        let mut args = Args::new("  one\ttwo   three ");
+        assert_eq!(args.single::<String>().unwrap(), "one");
+        assert_eq!(args.single::<String>().unwrap(), "two");
+        assert_eq!(args.remaining(), "three");
+        assert_eq!(args.single::<String>().unwrap(), "three");
+        assert!(args.is_empty());
+        assert_eq!(args.single::<String>(), Err(ArgumentError::Missing));
+    }
+
+    #[test]
+    fn quoted_arguments_keep_whitespace() {
+        let mut args = Args::new(r#""hello  world" after "" end"#);
+        assert_eq!(args.single::<String>().unwrap(), "hello  world");
+        assert_eq!(args.single::<String>().unwrap(), "after");
+        assert_eq!(args.single::<String>().unwrap(), "");
+        assert_eq!(args.single::<String>().unwrap(), "end");
+    }
+
+    #[test]
+    fn quotes_only_start_arguments() {
+        let mut args = Args::new(r#"say"hi" there"#);
+        assert_eq!(args.single::<String>().unwrap(), r#"say"hi""#);
+        assert_eq!(args.single::<String>().unwrap(), "there");
+    }
+
+    #[test]
+    fn escapes_inside_quotes() {
+        let mut args = Args::new(r#""a \"quote\" and \\ \n" next"#);
+        assert_eq!(args.single::<String>().unwrap(), r#"a "quote" and \ \n"#);
+        assert_eq!(args.single::<String>().unwrap(), "next");
+    }
+
+    #[test]
+    fn unclosed_quotes_fail_without_consuming() {
+        let mut args = Args::new(r#""never closed \""#);
+        assert_eq!(args.single::<String>(), Err(ArgumentError::UnclosedQuote));
+        assert_eq!(args.remaining(), r#""never closed \""#);
+        assert_eq!(args.rest().unwrap(), r#""never closed \""#);
+    }
+
+    #[test]
+    fn invalid_arguments_are_not_consumed() {
+        let mut args = Args::new("abc 12");
+        assert_eq!(
+            args.single::<u8>(),
+            Err(ArgumentError::Invalid {
+                argument: "abc".to_string(),
+                expected: "an integer",
+            })
+        );
+        assert_eq!(args.single::<String>().unwrap(), "abc");
+        assert_eq!(args.single::<u8>().unwrap(), 12);
+        assert!(args.single::<i8>().is_err());
+    }
+
+    #[test]
+    fn optional_and_rest() {
+        let mut args = Args::new("5 the rest  of it ");
+        assert_eq!(args.optional::<u32>().unwrap(), Some(5));
+        assert_eq!(args.rest().unwrap(), "the rest  of it");
+        assert_eq!(args.optional::<u32>().unwrap(), None);
+        assert_eq!(args.rest(), Err(ArgumentError::Missing));
+    }
+
+    #[test]
+    fn mentions_and_bare_ids() {
+        let mut args = Args::new(&format!("<@{0}> {0} <#{0}>", ID));
+        assert_eq!(
+            args.single::<UserMention>().unwrap(),
+            UserMention(ID.to_string())
+        );
+        assert_eq!(
+            args.single::<UserMention>().unwrap(),
+            UserMention(ID.to_string())
+        );
+        assert_eq!(
+            args.single::<ChannelMention>().unwrap(),
+            ChannelMention(ID.to_string())
+        );
+    }
+
+    #[test]
+    fn invalid_mentions() {
+        let wrong_sigil = format!("<#{0}>", ID);
+        assert_eq!(
+            UserMention::from_argument(&wrong_sigil),
+            Err(ArgumentError::Invalid {
+                argument: wrong_sigil.clone(),
+                expected: "a user mention",
+            })
+        );
+        assert!(ChannelMention::from_argument(&format!("<@{0}>", ID)).is_err());
+        assert!(UserMention::from_argument(&format!("<@{0}", ID)).is_err());
+        assert!(UserMention::from_argument(&ID.to_lowercase()).is_err());
+        assert!(UserMention::from_argument(&ID[1..]).is_err());
+        assert!(UserMention::from_argument("<@>").is_err());
+    }
+}
@@ -0,0 +1,286 @@
+use crate::data::channel::Channel;
+use crate::data::permissions::{ChannelPermission, ServerPermission};
+use crate::framework::{CommandContext, CommandError};
+use crate::permissions::PermissionCalculator;
+use crate::{RevoltRs, RevoltRsError};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// What a cooldown counts uses per.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bucket {
+    User,
+    Channel,
+    /// Per server, and per channel outside of servers.
+    Server,
+}
+
+/// Allows a command to be used `uses` times per `window` in each bucket.
+pub struct Cooldown {
+    bucket: Bucket,
+    uses: u32,
+    window: Duration,
+    used: Mutex<HashMap<String, (Instant, u32)>>,
+}
+
+impl Cooldown {
+    pub fn new(bucket: Bucket, uses: u32, window: Duration) -> Self {
+        Cooldown {
+            bucket,
+            uses,
+            window,
+            used: Mutex::default(),
+        }
+    }
+    fn key(&self, ctx: &CommandContext) -> String {
+        match (self.bucket, &ctx.server_id) {
+            (Bucket::User, _) => ctx.message.author.clone(),
+            (Bucket::Server, Some(server_id)) => server_id.clone(),
+            (Bucket::Channel, _) | (Bucket::Server, None) => ctx.message.channel.clone(),
+        }
+    }
+    /// Counts a use, or returns how long until the command can be used
+    /// again when it is cooling down.
+    fn try_acquire(&self, ctx: &CommandContext) -> Result<(), Duration> {
+        let key = self.key(ctx);
+        let mut used = self.used.lock().unwrap();
+        used.retain(|_, (start, _)| start.elapsed() < self.window);
+        let (start, uses) = used.entry(key).or_insert_with(|| (Instant::now(), 0));
+        if *uses >= self.uses {
+            return Err(self.window.saturating_sub(start.elapsed()));
+        }
+        *uses += 1;
+        Ok(())
+    }
+    /// Gives back a use taken by `try_acquire`.
+    fn release(&self, ctx: &CommandContext) {
+        let key = self.key(ctx);
+        if let Some((_, uses)) = self.used.lock().unwrap().get_mut(&key) {
+            *uses = uses.saturating_sub(1);
+        }
+    }
+}
+
+pub(crate) enum Scope {
+    Any,
+    Direct,
+    Server,
+}
+
+/// The requirements a command declares, checked before it runs.
+pub(crate) struct Checks {
+    pub(crate) scope: Scope,
+    pub(crate) owners_only: bool,
+    pub(crate) permissions: (ServerPermission, ChannelPermission),
+    pub(crate) bot_permissions: (ServerPermission, ChannelPermission),
+    pub(crate) cooldowns: Vec<Cooldown>,
+}
+
+impl Checks {
+    pub(crate) fn new() -> Self {
+        Checks {
+            scope: Scope::Any,
+            owners_only: false,
+            permissions: (ServerPermission::empty(), ChannelPermission::empty()),
+            bot_permissions: (ServerPermission::empty(), ChannelPermission::empty()),
+            cooldowns: Vec::new(),
+        }
+    }
+    pub(crate) fn needs_bot(&self) -> bool {
+        !is_empty(self.bot_permissions)
+    }
+    pub(crate) fn needs_channel(&self) -> bool {
+        !matches!(self.scope, Scope::Any) || !is_empty(self.permissions) || self.needs_bot()
+    }
+    /// Checks everything except cooldowns first, so a command that fails a
+    /// check does not count as used.
+    pub(crate) async fn run(
+        &self,
+        ctx: &CommandContext,
+        channel: Option<&Channel>,
+        owners: &[String],
+        bot_id: Option<&str>,
+    ) -> Result<(), CommandError> {
+        match (&self.scope, &ctx.server_id) {
+            (Scope::Direct, Some(_)) => return Err(CommandError::DirectOnly),
+            (Scope::Server, None) => return Err(CommandError::ServerOnly),
+            _ => {}
+        }
+        if self.owners_only && !owners.contains(&ctx.message.author) {
+            return Err(CommandError::OwnerOnly);
+        }
+        if !is_empty(self.permissions) {
+            let (server, channel) = missing(
+                ctx.http(),
+                &ctx.message.author,
+                channel,
+                ctx.server_id.as_deref(),
+                self.permissions,
+            )
+            .await?;
+            if !server.is_empty() || !channel.is_empty() {
+                return Err(CommandError::MissingPermissions { server, channel });
+            }
+        }
+        if let Some(bot_id) = bot_id.filter(|_| self.needs_bot()) {
+            let (server, channel) = missing(
+                ctx.http(),
+                bot_id,
+                channel,
+                ctx.server_id.as_deref(),
+                self.bot_permissions,
+            )
+            .await?;
+            if !server.is_empty() || !channel.is_empty() {
+                return Err(CommandError::BotMissingPermissions { server, channel });
+            }
+        }
+        for (acquired, cooldown) in self.cooldowns.iter().enumerate() {
+            if let Err(remaining) = cooldown.try_acquire(ctx) {
+                for cooldown in &self.cooldowns[..acquired] {
+                    cooldown.release(ctx);
+                }
+                return Err(CommandError::Cooldown { remaining });
+            }
+        }
+        Ok(())
+    }
+}
+
+fn is_empty(permissions: (ServerPermission, ChannelPermission)) -> bool {
+    permissions.0.is_empty() && permissions.1.is_empty()
+}
+
+/// The required permissions a user does not have in a channel.
+async fn missing(
+    http: &RevoltRs,
+    user_id: &str,
+    channel: Option<&Channel>,
+    server_id: Option<&str>,
+    required: (ServerPermission, ChannelPermission),
+) -> Result<(ServerPermission, ChannelPermission), RevoltRsError> {
+    let server = match server_id {
+        Some(server_id) => Some(http.fetch_server(server_id).await?),
+        None => None,
+    };
+    let member = match server_id {
+        Some(server_id) => Some(http.fetch_member(server_id, user_id).await?),
+        None => None,
+    };
+    let mut calculator = PermissionCalculator::new(user_id);
+    if let Some(channel) = channel {
+        calculator = calculator.channel(channel);
+    }
+    if let Some(server) = &server {
+        calculator = calculator.server(server);
+    }
+    if let Some(member) = &member {
+        calculator = calculator.member(member);
+    }
+    Ok((
+        required.0 - calculator.server_permissions(),
+        required.1 - calculator.channel_permissions(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::message::Message;
+    use crate::Context;
+
+    fn ctx(author: &str, channel: &str, server_id: Option<&str>) -> CommandContext {
+        let message: Message = serde_json::from_value(serde_json::json!({
+            "_id": "MESSAGE",
+            "channel": channel,
+            "author": author,
+            "content": "!command",
+        }))
+        .unwrap();
+        CommandContext {
+            ctx: Context {
+                http: RevoltRs::new("token").unwrap(),
+            },
+            message,
+            server_id: server_id.map(str::to_string),
+            prefix: "!".to_string(),
+            command: "command".to_string(),
+        }
+    }
+
+    #[test]
+    fn keys_follow_the_bucket() {
+        let in_server = ctx("USER", "CHANNEL", Some("SERVER"));
+        let direct = ctx("USER", "DM", None);
+        let key = |bucket, ctx| Cooldown::new(bucket, 1, Duration::from_secs(1)).key(ctx);
+        assert_eq!(key(Bucket::User, &in_server), "USER");
+        assert_eq!(key(Bucket::User, &direct), "USER");
+        assert_eq!(key(Bucket::Channel, &in_server), "CHANNEL");
+        assert_eq!(key(Bucket::Server, &in_server), "SERVER");
+        assert_eq!(key(Bucket::Server, &direct), "DM");
+    }
+
+    #[test]
+    fn uses_are_counted_per_key() {
+        let cooldown = Cooldown::new(Bucket::User, 2, Duration::from_secs(60));
+        let user = ctx("USER", "CHANNEL", Some("SERVER"));
+        let other = ctx("OTHER", "CHANNEL", Some("SERVER"));
+        assert!(cooldown.try_acquire(&user).is_ok());
+        assert!(cooldown.try_acquire(&user).is_ok());
+        let remaining = cooldown.try_acquire(&user).unwrap_err();
+        assert!(remaining > Duration::ZERO && remaining <= Duration::from_secs(60));
+        assert!(cooldown.try_acquire(&other).is_ok());
+        cooldown.release(&user);
+        assert!(cooldown.try_acquire(&user).is_ok());
+    }
+
+    #[test]
+    fn uses_expire_with_the_window() {
+        let cooldown = Cooldown::new(Bucket::Channel, 1, Duration::from_millis(20));
+        let ctx = ctx("USER", "CHANNEL", None);
+        assert!(cooldown.try_acquire(&ctx).is_ok());
+        assert!(cooldown.try_acquire(&ctx).is_err());
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(cooldown.try_acquire(&ctx).is_ok());
+    }
+
+    #[async_std::test]
+    async fn cooldowns_report_the_time_left() {
+        let mut checks = Checks::new();
+        checks
+            .cooldowns
+            .push(Cooldown::new(Bucket::User, 1, Duration::from_secs(60)));
+        let ctx = ctx("USER", "CHANNEL", Some("SERVER"));
+        assert!(checks.run(&ctx, None, &[], None).await.is_ok());
+        match checks.run(&ctx, None, &[], None).await {
+            Err(CommandError::Cooldown { remaining }) => {
+                assert!(remaining > Duration::ZERO && remaining <= Duration::from_secs(60))
+            }
+            result => panic!("expected a cooldown, got {:?}", result),
+        }
+    }
+
+    #[async_std::test]
+    async fn earlier_uses_are_given_back_when_a_later_cooldown_rejects() {
+        let mut checks = Checks::new();
+        checks
+            .cooldowns
+            .push(Cooldown::new(Bucket::User, 2, Duration::from_secs(60)));
+        checks
+            .cooldowns
+            .push(Cooldown::new(Bucket::Server, 1, Duration::from_secs(60)));
+        let user = ctx("USER", "CHANNEL", Some("SERVER"));
+        let other = ctx("OTHER", "CHANNEL", Some("SERVER"));
+        assert!(checks.run(&other, None, &[], None).await.is_ok());
+        for _ in 0..3 {
+            assert!(matches!(
+                checks.run(&user, None, &[], None).await,
+                Err(CommandError::Cooldown { .. })
+            ));
+        }
+        let user_cooldown = &checks.cooldowns[0];
+        assert!(user_cooldown.try_acquire(&user).is_ok());
+        assert!(user_cooldown.try_acquire(&user).is_ok());
+    }
+}
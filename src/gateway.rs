@@ -0,0 +1,252 @@
+use crate::data::event::{ClientMessage, Event};
+use crate::{Authentication, RetryPolicy, RevoltRs, RevoltRsError};
+use async_std::channel::{self, Receiver, Sender};
+use async_std::future;
+use async_std::task;
+use async_tungstenite::async_std::{connect_async, ConnectStream};
+use async_tungstenite::tungstenite::Message as WsMessage;
+use async_tungstenite::WebSocketStream;
+use futures::{stream, Sink, SinkExt, Stream, StreamExt};
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(15);
+const RECONNECT_INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const RECONNECT_MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+type Socket = WebSocketStream<ConnectStream>;
+
+/// A connection to the Revolt events websocket (Bonfire).
+///
+/// Events are read by polling the gateway as a `Stream`. Heartbeats are sent
+/// in the background for as long as the gateway is alive. When the connection
+/// drops or stops answering heartbeats, the gateway reconnects and
+/// authenticates again by itself, then yields `Event::Reconnected`.
+pub struct Gateway {
+    events: Receiver<Result<Event, RevoltRsError>>,
+    commands: Sender<ClientMessage>,
+}
+
+enum Incoming {
+    Socket(Result<WsMessage, async_tungstenite::tungstenite::Error>),
+    Closed,
+    Heartbeat,
+    Command(ClientMessage),
+}
+
+impl Gateway {
+    /// Connects to the websocket of the instance the client talks to and
+    /// authenticates with the client's token.
+    pub async fn connect(revolt: &RevoltRs) -> Result<Self, RevoltRsError> {
+        let reconnect = RetryPolicy {
+            max_retries: u32::MAX,
+            initial_backoff: RECONNECT_INITIAL_BACKOFF,
+            max_backoff: RECONNECT_MAX_BACKOFF,
+        };
+        Gateway::connect_with_reconnect_policy(revolt, reconnect).await
+    }
+    /// Like `connect`, with `reconnect` deciding how often and how patiently
+    /// the gateway tries to reconnect before giving up.
+    pub async fn connect_with_reconnect_policy(
+        revolt: &RevoltRs,
+        reconnect: RetryPolicy,
+    ) -> Result<Self, RevoltRsError> {
+        let token = match revolt.authentication() {
+            Authentication::Bot(token) | Authentication::Session(token) => token.clone(),
+            Authentication::None => return Err(RevoltRsError::Unauthenticated),
+        };
+        let url = format!(
+            "{0}/?format=json",
+            revolt.node().await?.ws.trim_end_matches('/')
+        );
+        let socket = connect(&url, &token).await?;
+        let (event_sender, events) = channel::unbounded();
+        let (commands, command_receiver) = channel::unbounded();
+        task::spawn(run(
+            url,
+            token,
+            socket,
+            event_sender,
+            command_receiver,
+            reconnect,
+        ));
+        Ok(Gateway { events, commands })
+    }
+    pub async fn begin_typing(&self, channel_id: &str) -> Result<(), RevoltRsError> {
+        self.send(ClientMessage::BeginTyping {
+            channel: channel_id.to_string(),
+        })
+        .await
+    }
+    pub async fn end_typing(&self, channel_id: &str) -> Result<(), RevoltRsError> {
+        self.send(ClientMessage::EndTyping {
+            channel: channel_id.to_string(),
+        })
+        .await
+    }
+    async fn send(&self, message: ClientMessage) -> Result<(), RevoltRsError> {
+        self.commands
+            .send(message)
+            .await
+            .map_err(|_| RevoltRsError::GatewayClosed)
+    }
+}
+
+impl Stream for Gateway {
+    type Item = Result<Event, RevoltRsError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.events).poll_next(cx)
+    }
+}
+
+/// Opens the websocket and authenticates, giving up when the server takes
+/// longer than a heartbeat to answer.
+async fn connect(url: &str, token: &str) -> Result<Socket, RevoltRsError> {
+    match future::timeout(HEARTBEAT_INTERVAL, handshake(url, token)).await {
+        Ok(result) => result,
+        Err(_) => Err(io::Error::new(
+            io::ErrorKind::TimedOut,
+            "the gateway did not answer the handshake in time",
+        )
+        .into()),
+    }
+}
+
+async fn handshake(url: &str, token: &str) -> Result<Socket, RevoltRsError> {
+    let (mut socket, _) = connect_async(url).await?;
+    send(
+        &mut socket,
+        &ClientMessage::Authenticate {
+            token: token.to_string(),
+        },
+    )
+    .await?;
+    while let Some(message) = socket.next().await {
+        match parse(message?)? {
+            Some(Event::Authenticated) => return Ok(socket),
+            Some(Event::Error { error }) => return Err(RevoltRsError::Gateway { error }),
+            _ => {}
+        }
+    }
+    Err(RevoltRsError::GatewayClosed)
+}
+
+async fn send<S>(sink: &mut S, message: &ClientMessage) -> Result<(), RevoltRsError>
+where
+    S: Sink<WsMessage, Error = async_tungstenite::tungstenite::Error> + Unpin,
+{
+    let text = serde_json::to_string(message)?;
+    sink.send(WsMessage::text(text)).await?;
+    Ok(())
+}
+
+fn parse(message: WsMessage) -> Result<Option<Event>, RevoltRsError> {
+    match message {
+        WsMessage::Text(text) => Ok(Some(serde_json::from_str(&text)?)),
+        _ => Ok(None),
+    }
+}
+
+enum SessionEnd {
+    Disconnected,
+    Stopped,
+}
+
+async fn run(
+    url: String,
+    token: String,
+    mut socket: Socket,
+    events: Sender<Result<Event, RevoltRsError>>,
+    commands: Receiver<ClientMessage>,
+    reconnect: RetryPolicy,
+) {
+    loop {
+        if let SessionEnd::Stopped = session(socket, &events, &commands).await {
+            return;
+        }
+        let mut attempt = 0;
+        socket = loop {
+            if events.is_closed() {
+                return;
+            }
+            task::sleep(reconnect.backoff(attempt)).await;
+            match connect(&url, &token).await {
+                Ok(socket) => break socket,
+                Err(error @ RevoltRsError::Gateway { .. }) => {
+                    let _ = events.send(Err(error)).await;
+                    return;
+                }
+                Err(error) if attempt >= reconnect.max_retries => {
+                    let _ = events.send(Err(error)).await;
+                    return;
+                }
+                Err(_) => attempt += 1,
+            }
+        };
+        if events.send(Ok(Event::Reconnected)).await.is_err() {
+            return;
+        }
+    }
+}
+
+/// Forwards the events of one connection until it drops, sending heartbeats
+/// and treating a heartbeat that went unanswered as a dead connection.
+async fn session(
+    socket: Socket,
+    events: &Sender<Result<Event, RevoltRsError>>,
+    commands: &Receiver<ClientMessage>,
+) -> SessionEnd {
+    let (mut sink, socket) = socket.split();
+    let socket = socket
+        .map(Incoming::Socket)
+        .chain(stream::once(async { Incoming::Closed }));
+    let heartbeat = stream::unfold((), |_| async {
+        task::sleep(HEARTBEAT_INTERVAL).await;
+        Some((Incoming::Heartbeat, ()))
+    });
+    let incoming = stream::select(
+        socket,
+        stream::select(heartbeat, commands.clone().map(Incoming::Command)),
+    );
+    futures::pin_mut!(incoming);
+    let mut ping = 0;
+    let mut awaiting_pong = false;
+    let end = loop {
+        let result = match incoming.next().await {
+            Some(Incoming::Socket(Ok(message))) => match parse(message) {
+                Ok(Some(Event::Pong { .. })) => {
+                    awaiting_pong = false;
+                    continue;
+                }
+                Ok(None) => continue,
+                Ok(Some(event)) => Ok(event),
+                Err(error) => Err(error),
+            },
+            Some(Incoming::Socket(Err(_))) | Some(Incoming::Closed) | None => {
+                break SessionEnd::Disconnected
+            }
+            Some(Incoming::Heartbeat) if events.is_closed() => break SessionEnd::Stopped,
+            Some(Incoming::Heartbeat) if awaiting_pong => break SessionEnd::Disconnected,
+            Some(Incoming::Heartbeat) => {
+                ping += 1;
+                awaiting_pong = true;
+                match send(&mut sink, &ClientMessage::Ping { data: ping }).await {
+                    Ok(()) => continue,
+                    Err(_) => break SessionEnd::Disconnected,
+                }
+            }
+            Some(Incoming::Command(command)) => match send(&mut sink, &command).await {
+                Ok(()) => continue,
+                Err(_) => break SessionEnd::Disconnected,
+            },
+        };
+        if events.send(result).await.is_err() {
+            break SessionEnd::Stopped;
+        }
+    };
+    let _ = sink.close(None).await;
+    end
+}
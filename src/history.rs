@@ -0,0 +1,223 @@
+use crate::data::message::{Message, SearchSort};
+use crate::data::user;
+use crate::{RevoltRs, RevoltRsError};
+use futures::stream::{self, BoxStream};
+use futures::{Stream, StreamExt};
+use rusty_ulid::Ulid;
+use std::collections::{HashMap, VecDeque};
+use std::pin::Pin;
+use std::sync::{Arc, RwLock};
+use std::task::{Context, Poll};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const PAGE_SIZE: usize = 100;
+
+/// Walks the messages of a channel, fetching a page at a time.
+///
+/// Messages are yielded newest first unless `oldest_first` is set. The
+/// window is exclusive on both ends.
+pub struct MessageHistory {
+    revolt: RevoltRs,
+    channel_id: String,
+    oldest_first: bool,
+    before: Option<String>,
+    after: Option<String>,
+    limit: Option<usize>,
+    include_users: bool,
+}
+
+impl MessageHistory {
+    pub(crate) fn new(revolt: RevoltRs, channel_id: &str) -> Self {
+        MessageHistory {
+            revolt,
+            channel_id: channel_id.to_string(),
+            oldest_first: false,
+            before: None,
+            after: None,
+            limit: None,
+            include_users: false,
+        }
+    }
+    pub fn oldest_first(mut self) -> Self {
+        self.oldest_first = true;
+        self
+    }
+    /// Only yields messages sent before the message with this id.
+    pub fn before(mut self, message_id: &str) -> Self {
+        self.before = Some(message_id.to_string());
+        self
+    }
+    /// Only yields messages sent after the message with this id.
+    pub fn after(mut self, message_id: &str) -> Self {
+        self.after = Some(message_id.to_string());
+        self
+    }
+    /// Only yields messages sent before `time`.
+    pub fn until(mut self, time: SystemTime) -> Self {
+        self.before = Some(ulid_at(time));
+        self
+    }
+    /// Only yields messages sent after `time`.
+    pub fn since(mut self, time: SystemTime) -> Self {
+        self.after = Some(ulid_at(time));
+        self
+    }
+    /// Stops after `limit` messages.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+    /// Fetches the authors of the messages along with them. They are stored
+    /// in the client's cache and can be read from `History::users`.
+    pub fn include_users(mut self) -> Self {
+        self.include_users = true;
+        self
+    }
+    pub fn stream(self) -> History {
+        let users = Arc::new(RwLock::new(HashMap::new()));
+        let state = State {
+            cursor: if self.oldest_first {
+                self.after.clone()
+            } else {
+                self.before.clone()
+            },
+            history: self,
+            users: users.clone(),
+            page: VecDeque::new(),
+            yielded: 0,
+            done: false,
+        };
+        let stream = stream::unfold(state, |mut state| async move {
+            loop {
+                if state
+                    .history
+                    .limit
+                    .is_some_and(|limit| state.yielded >= limit)
+                {
+                    return None;
+                }
+                if let Some(message) = state.page.pop_front() {
+                    state.yielded += 1;
+                    return Some((Ok(message), state));
+                }
+                if state.done {
+                    return None;
+                }
+                if let Err(error) = state.next_page().await {
+                    state.done = true;
+                    return Some((Err(error), state));
+                }
+            }
+        });
+        History {
+            stream: stream.boxed(),
+            users,
+        }
+    }
+}
+
+/// The stream of messages produced by `MessageHistory::stream`.
+pub struct History {
+    stream: BoxStream<'static, Result<Message, RevoltRsError>>,
+    users: Arc<RwLock<HashMap<String, user::User>>>,
+}
+
+impl History {
+    /// The authors fetched so far when `include_users` is set.
+    pub fn user(&self, user_id: &str) -> Option<user::User> {
+        self.users.read().unwrap().get(user_id).cloned()
+    }
+    pub fn users(&self) -> Vec<user::User> {
+        self.users.read().unwrap().values().cloned().collect()
+    }
+}
+
+impl Stream for History {
+    type Item = Result<Message, RevoltRsError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.stream.as_mut().poll_next(cx)
+    }
+}
+
+struct State {
+    history: MessageHistory,
+    cursor: Option<String>,
+    users: Arc<RwLock<HashMap<String, user::User>>>,
+    page: VecDeque<Message>,
+    yielded: usize,
+    done: bool,
+}
+
+impl State {
+    async fn next_page(&mut self) -> Result<(), RevoltRsError> {
+        let history = &self.history;
+        let page_size = match history.limit {
+            Some(limit) => (limit - self.yielded).min(PAGE_SIZE),
+            None => PAGE_SIZE,
+        };
+        let (before, after, sort) = if history.oldest_first {
+            (
+                history.before.clone(),
+                self.cursor.clone(),
+                SearchSort::Oldest,
+            )
+        } else {
+            (
+                self.cursor.clone(),
+                history.after.clone(),
+                SearchSort::Latest,
+            )
+        };
+        let revolt = &history.revolt;
+        let fetched = revolt
+            .fetch_messages(
+                &history.channel_id,
+                Some(page_size as i32),
+                before,
+                after,
+                sort,
+                None,
+                Some(history.include_users).filter(|include| *include),
+            )
+            .await?;
+        let messages = fetched.messages.unwrap_or_default();
+        let (users, members) = (fetched.users, fetched.members);
+        if let Some(cache) = revolt.cache() {
+            for user in users.iter().flatten() {
+                let _ = cache.insert_user(user.clone()).await;
+            }
+            for member in members.into_iter().flatten() {
+                let _ = cache.insert_member(member).await;
+            }
+        }
+        let mut known = self.users.write().unwrap();
+        for user in users.into_iter().flatten() {
+            known.insert(user.id.clone(), user);
+        }
+        self.done = messages.len() < page_size;
+        self.cursor = messages.last().map(|message| message.id.clone());
+        self.done |= self.cursor.is_none();
+        let in_window = |message: &Message| {
+            history
+                .before
+                .as_ref()
+                .is_none_or(|before| message.id < *before)
+                && history
+                    .after
+                    .as_ref()
+                    .is_none_or(|after| message.id > *after)
+        };
+        self.page.extend(messages.into_iter().filter(in_window));
+        Ok(())
+    }
+}
+
+/// The smallest ULID of a message sent at `time`.
+fn ulid_at(time: SystemTime) -> String {
+    let timestamp = time
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis())
+        .unwrap_or(0);
+    Ulid::from(timestamp << 80).to_string()
+}
@@ -0,0 +1,100 @@
+use crate::data::error::ApiError;
+use crate::data::internal;
+use crate::{ratelimit, retry};
+use crate::{Config, RevoltRsError};
+use async_std::task;
+use reqwest::{Response, StatusCode};
+use std::time::Duration;
+
+pub(crate) struct Request<'a> {
+    config: &'a Config,
+    builder: reqwest::RequestBuilder,
+    retry_safe: bool,
+}
+
+impl<'a> Request<'a> {
+    pub(crate) fn new(config: &'a Config, builder: reqwest::RequestBuilder) -> Self {
+        Request {
+            config,
+            builder,
+            retry_safe: false,
+        }
+    }
+    pub(crate) fn body<T: Into<reqwest::Body>>(mut self, body: T) -> Self {
+        self.builder = self.builder.body(body);
+        self
+    }
+    pub(crate) fn query<T: serde::Serialize + ?Sized>(mut self, query: &T) -> Self {
+        self.builder = self.builder.query(query);
+        self
+    }
+    pub(crate) fn multipart(mut self, form: reqwest::multipart::Form) -> Self {
+        self.builder = self.builder.multipart(form);
+        self
+    }
+    /// Marks a non-idempotent request as safe to re-send, because the API
+    /// deduplicates it by its nonce.
+    pub(crate) fn retry_safe(mut self) -> Self {
+        self.retry_safe = true;
+        self
+    }
+    /// Sends the request once its rate limit bucket allows it, retrying
+    /// transient failures according to the client's retry policy.
+    pub(crate) async fn send(self) -> Result<Response, RevoltRsError> {
+        let (client, request) = self.builder.build_split();
+        let mut request = request?;
+        let route = ratelimit::route(request.method(), request.url());
+        let idempotent = self.retry_safe || retry::is_idempotent(request.method());
+        let ratelimiter = &self.config.ratelimiter;
+        let policy = &self.config.retry_policy;
+        let mut retries = 0;
+        loop {
+            let retry = request.try_clone().filter(|_| retries < policy.max_retries);
+            ratelimiter.acquire(&route).await;
+            let response = match client.execute(request).await {
+                Ok(response) => response,
+                Err(error) => match retry {
+                    Some(retry) if error.is_connect() || (idempotent && error.is_timeout()) => {
+                        task::sleep(policy.backoff(retries)).await;
+                        request = retry;
+                        retries += 1;
+                        continue;
+                    }
+                    _ => return Err(error.into()),
+                },
+            };
+            let info = ratelimiter.update(&route, response.headers());
+            let status = response.status();
+            let (retry, wait) = match retry {
+                Some(retry) if status == StatusCode::TOO_MANY_REQUESTS => {
+                    let body = response.text().await?;
+                    let retry_after = serde_json::from_str::<internal::RateLimited>(&body)
+                        .map(|limited| Duration::from_millis(limited.retry_after))
+                        .ok()
+                        .or_else(|| info.map(|info| info.reset_after))
+                        .unwrap_or_default();
+                    (retry, retry_after.max(policy.backoff(retries)))
+                }
+                Some(retry) if idempotent && status.is_server_error() => {
+                    (retry, policy.backoff(retries))
+                }
+                _ => return check_status(response).await,
+            };
+            task::sleep(wait).await;
+            request = retry;
+            retries += 1;
+        }
+    }
+}
+
+async fn check_status(response: Response) -> Result<Response, RevoltRsError> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let body = response.text().await?;
+    match serde_json::from_str::<ApiError>(&body) {
+        Ok(error) => Err(RevoltRsError::Api { status, error }),
+        Err(_) => Err(RevoltRsError::Status { status, body }),
+    }
+}
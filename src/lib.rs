@@ -1,5 +1,5 @@
 #![forbid(unsafe_code)]
-use reqwest::header;
+use reqwest::{header, Method};
 use rusty_ulid::generate_ulid_string;
 use thiserror::Error;
 
@@ -7,17 +7,91 @@ pub mod data {
     pub mod autumn;
     pub mod server;
     pub mod channel;
+    pub mod error;
+    pub mod event;
     pub mod internal;
     pub mod message;
+    pub mod node;
+    pub mod permissions;
     pub mod user;
 }
 
-use crate::data::{channel, internal, message, user};
+mod files;
+mod history;
+mod builder;
+pub mod cache;
+mod http;
+mod purge;
+mod ratelimit;
+mod retry;
 
+pub mod client;
+pub mod framework;
+pub mod gateway;
+pub mod permissions;
+
+pub use crate::builder::RevoltRsBuilder;
+pub use crate::cache::Cache;
+pub use crate::client::{Client, Context, EventHandler};
+pub use crate::framework::Framework;
+pub use crate::gateway::Gateway;
+pub use crate::history::{History, MessageHistory};
+pub use crate::permissions::PermissionCalculator;
+pub use crate::purge::Purge;
+pub use crate::retry::RetryPolicy;
+use crate::data::permissions::{ChannelPermission, ServerPermission};
+use crate::data::{autumn, channel, error, internal, message, node, server, user};
+use crate::http::Request;
+use crate::ratelimit::RateLimiter;
+use std::fmt;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+#[derive(Clone)]
+pub enum Authentication {
+    Bot(String),
+    Session(String),
+    None,
+}
+
+// Tokens are left out, so clients can be logged without leaking them.
+impl fmt::Debug for Authentication {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let hidden = format_args!("<hidden>");
+        match self {
+            Authentication::Bot(_) => f.debug_tuple("Bot").field(&hidden).finish(),
+            Authentication::Session(_) => f.debug_tuple("Session").field(&hidden).finish(),
+            Authentication::None => f.write_str("None"),
+        }
+    }
+}
+
+struct Config {
+    api_url: String,
+    authentication: Authentication,
+    authentication_header: Option<(header::HeaderName, header::HeaderValue)>,
+    user_agent: header::HeaderValue,
+    timeout: Option<Duration>,
+    node: RwLock<Option<Arc<node::NodeInfo>>>,
+    autumn: RwLock<Option<Arc<autumn::AutumnConfig>>>,
+    ratelimiter: RateLimiter,
+    retry_policy: RetryPolicy,
+    cache: Option<Arc<Cache>>,
+}
+
+/// A handle to the Revolt API. Cloning it is cheap: clones share the same
+/// connection pool and configuration.
+#[derive(Clone)]
 pub struct RevoltRs {
     pub client: reqwest::Client,
+    config: Arc<Config>,
 }
 
+const _: fn() = || {
+    fn assert_shareable<T: Clone + Send + Sync + 'static>() {}
+    assert_shareable::<RevoltRs>();
+};
+
 #[derive(Error, Debug)]
 pub enum RevoltRsError {
     #[error("HTTP request failed")]
@@ -30,46 +104,193 @@ pub enum RevoltRsError {
         #[from]
         source: serde_json::Error,
     },
+    #[error("reading or writing a file failed")]
+    Io {
+        #[from]
+        source: std::io::Error,
+    },
     #[error("got an invalid header value")]
     Header {
         #[from]
         source: reqwest::header::InvalidHeaderValue,
     },
+    #[error("the API responded with {status}: {error:?}")]
+    Api {
+        status: reqwest::StatusCode,
+        error: error::ApiError,
+    },
+    #[error("the API responded with {status}")]
+    Status {
+        status: reqwest::StatusCode,
+        body: String,
+    },
+    #[error("websocket connection failed")]
+    WebSocket {
+        #[source]
+        source: Box<async_tungstenite::tungstenite::Error>,
+    },
+    #[error("the gateway returned an error: {error}")]
+    Gateway { error: String },
+    #[error("the gateway connection is closed")]
+    GatewayClosed,
+    #[error("the client has no token to authenticate with")]
+    Unauthenticated,
+    #[error("the instance does not run a file server")]
+    AutumnUnavailable,
+    #[error("the file server does not accept {tag:?} uploads")]
+    UnknownTag { tag: autumn::AttachmentTag },
+    #[error("the file is {size} bytes, at most {max_size} bytes are allowed")]
+    FileTooLarge { size: u64, max_size: u64 },
 }
 
-const API_URL: &str = "https://api.revolt.chat";
+impl From<async_tungstenite::tungstenite::Error> for RevoltRsError {
+    fn from(source: async_tungstenite::tungstenite::Error) -> Self {
+        RevoltRsError::WebSocket {
+            source: Box::new(source),
+        }
+    }
+}
+
+impl RevoltRsError {
+    /// The HTTP status code, if the request reached the API and failed there.
+    pub fn status(&self) -> Option<reqwest::StatusCode> {
+        match self {
+            RevoltRsError::Api { status, .. } | RevoltRsError::Status { status, .. } => {
+                Some(*status)
+            }
+            RevoltRsError::Reqwest { source } => source.status(),
+            _ => None,
+        }
+    }
+    pub fn api_error(&self) -> Option<&error::ApiError> {
+        match self {
+            RevoltRsError::Api { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+pub const API_URL: &str = "https://api.revolt.chat";
 
 impl RevoltRs {
+    /// Creates a client authenticated with a bot token.
     pub fn new(token: &str) -> Result<Self, RevoltRsError> {
-        let mut headers = header::HeaderMap::new();
-        headers.insert("X-String-TOKEN", header::HeaderValue::from_str(token)?);
-        let client = reqwest::Client::builder()
-            .default_headers(headers)
-            .build()?;
-        Ok(RevoltRs { client: client })
-    }
-    pub async fn fetch_user(self, user_id: &str) -> Result<user::User, RevoltRsError> {
-        let url = format!("{0}/users/{1}", API_URL, user_id);
-        let data = self.client.get(url).send().await?.text().await?;
+        RevoltRs::builder().bot_token(token).build()
+    }
+    /// Creates a bot client talking to the Revolt instance at `api_url`, e.g. a
+    /// self-hosted node or a local mock server.
+    pub fn with_api_url(token: &str, api_url: &str) -> Result<Self, RevoltRsError> {
+        RevoltRs::builder().api_url(api_url).bot_token(token).build()
+    }
+    pub fn builder() -> RevoltRsBuilder {
+        RevoltRsBuilder::new()
+    }
+    pub fn api_url(&self) -> &str {
+        &self.config.api_url
+    }
+    pub fn authentication(&self) -> &Authentication {
+        &self.config.authentication
+    }
+    /// The entity cache, if the client was built with one. `fetch_user`,
+    /// `fetch_channel`, `fetch_server` and `fetch_member` answer from it
+    /// before asking the API, and store what the API returns in it. Errors
+    /// from the cache backend are treated as cache misses.
+    pub fn cache(&self) -> Option<&Arc<Cache>> {
+        self.config.cache.as_ref()
+    }
+    fn request(&self, method: Method, url: String) -> Request<'_> {
+        let mut request = self
+            .client
+            .request(method, url)
+            .header(header::USER_AGENT, self.config.user_agent.clone());
+        if let Some((name, value)) = &self.config.authentication_header {
+            request = request.header(name.clone(), value.clone());
+        }
+        if let Some(timeout) = self.config.timeout {
+            request = request.timeout(timeout);
+        }
+        Request::new(&self.config, request)
+    }
+    fn get(&self, url: String) -> Request<'_> {
+        self.request(Method::GET, url)
+    }
+    fn post(&self, url: String) -> Request<'_> {
+        self.request(Method::POST, url)
+    }
+    fn put(&self, url: String) -> Request<'_> {
+        self.request(Method::PUT, url)
+    }
+    fn patch(&self, url: String) -> Request<'_> {
+        self.request(Method::PATCH, url)
+    }
+    fn delete(&self, url: String) -> Request<'_> {
+        self.request(Method::DELETE, url)
+    }
+    pub async fn fetch_node_info(&self) -> Result<node::NodeInfo, RevoltRsError> {
+        let url = format!("{0}/", self.api_url());
+        let data = self.get(url).send().await?.text().await?;
+        let node_info: node::NodeInfo = serde_json::from_str(&data)?;
+        Ok(node_info)
+    }
+    /// Fetches the node information from the API root so the autumn, january
+    /// and websocket URLs of this instance become available.
+    pub async fn discover(&self) -> Result<Arc<node::NodeInfo>, RevoltRsError> {
+        let node_info = Arc::new(self.fetch_node_info().await?);
+        *self.config.node.write().unwrap() = Some(node_info.clone());
+        Ok(node_info)
+    }
+    /// Returns the discovered node information, fetching it on first use.
+    pub async fn node(&self) -> Result<Arc<node::NodeInfo>, RevoltRsError> {
+        match self.node_info() {
+            Some(node_info) => Ok(node_info),
+            None => self.discover().await,
+        }
+    }
+    pub fn node_info(&self) -> Option<Arc<node::NodeInfo>> {
+        self.config.node.read().unwrap().clone()
+    }
+    pub fn autumn_url(&self) -> Option<String> {
+        self.node_info()
+            .filter(|node| node.features.autumn.enabled)
+            .map(|node| node.features.autumn.url.clone())
+    }
+    pub fn january_url(&self) -> Option<String> {
+        self.node_info()
+            .filter(|node| node.features.january.enabled)
+            .map(|node| node.features.january.url.clone())
+    }
+    pub fn ws_url(&self) -> Option<String> {
+        self.node_info().map(|node| node.ws.clone())
+    }
+    pub async fn fetch_user(&self, user_id: &str) -> Result<user::User, RevoltRsError> {
+        if let Some(cache) = self.cache() {
+            if let Ok(Some(user)) = cache.user(user_id).await {
+                return Ok(user);
+            }
+        }
+        let url = format!("{0}/users/{1}", self.api_url(), user_id);
+        let data = self.get(url).send().await?.text().await?;
         let user: user::User = serde_json::from_str(&data)?;
+        if let Some(cache) = self.cache() {
+            let _ = cache.insert_user(user.clone()).await;
+        }
         Ok(user)
     }
     pub async fn edit_user(
-        self,
+        &self,
         status: Option<user::Status>,
         profile: Option<user::Profile>,
         avatar: Option<String>,
         remove: Option<String>,
     ) -> Result<String, RevoltRsError> {
         let parameters = internal::EditUser {
-            status: status,
-            profile: profile,
-            avatar: avatar,
-            remove: remove,
+            status,
+            profile,
+            avatar,
+            remove,
         };
-        let url = format!("{0}/users/@me", API_URL);
+        let url = format!("{0}/users/@me", self.api_url());
         let data = self
-            .client
             .patch(url)
             .body(serde_json::to_string(&parameters)?)
             .send()
@@ -78,113 +299,119 @@ impl RevoltRs {
             .await?;
         Ok(data)
     }
-    pub async fn fetch_user_profile(self, user_id: &str) -> Result<user::Profile, RevoltRsError> {
-        let url = format!("{0}/users/{1}/profile", API_URL, user_id);
-        let data = self.client.get(url).send().await?.text().await?;
+    pub async fn fetch_user_profile(&self, user_id: &str) -> Result<user::Profile, RevoltRsError> {
+        let url = format!("{0}/users/{1}/profile", self.api_url(), user_id);
+        let data = self.get(url).send().await?.text().await?;
         let profile: user::Profile = serde_json::from_str(&data)?;
         Ok(profile)
     }
-    pub async fn fetch_default_avatar(self, user_id: &str) -> Result<Vec<u8>, RevoltRsError> {
-        let url = format!("{0}/users/{1}/default_avatar", API_URL, user_id);
+    pub async fn fetch_default_avatar(&self, user_id: &str) -> Result<Vec<u8>, RevoltRsError> {
+        let url = format!("{0}/users/{1}/default_avatar", self.api_url(), user_id);
         let data = self
-            .client
             .get(url)
             .send()
             .await?
-            .text()
+            .bytes()
             .await?
-            .into_bytes();
+            .to_vec();
         Ok(data)
     }
-    pub async fn fetch_mutual_friends(self, user_id: &str) -> Result<Vec<String>, RevoltRsError> {
-        let url = format!("{0}/users/{1}/mutual", API_URL, user_id);
-        let data = self.client.get(url).send().await?.text().await?;
+    pub async fn fetch_mutual_friends(&self, user_id: &str) -> Result<Vec<String>, RevoltRsError> {
+        let url = format!("{0}/users/{1}/mutual", self.api_url(), user_id);
+        let data = self.get(url).send().await?.text().await?;
         let mutual: Vec<String> = serde_json::from_str(&data)?;
         Ok(mutual)
     }
-    pub async fn fetch_direct_message_channels(self) -> Result<channel::DMChannel, RevoltRsError> {
-        let url = format!("{0}/users/dms", API_URL);
-        let data = self.client.get(url).send().await?.text().await?;
+    pub async fn fetch_direct_message_channels(&self) -> Result<channel::DMChannel, RevoltRsError> {
+        let url = format!("{0}/users/dms", self.api_url());
+        let data = self.get(url).send().await?.text().await?;
         let dm_channel: channel::DMChannel = serde_json::from_str(&data)?;
         Ok(dm_channel)
     }
     pub async fn open_direct_message(
-        self,
+        &self,
         user_id: &str,
     ) -> Result<channel::DMChannel, RevoltRsError> {
-        let url = format!("{0}/users/{1}/dm", API_URL, user_id);
-        let data = self.client.get(url).send().await?.text().await?;
+        let url = format!("{0}/users/{1}/dm", self.api_url(), user_id);
+        let data = self.get(url).send().await?.text().await?;
         let dm_channel: channel::DMChannel = serde_json::from_str(&data)?;
         Ok(dm_channel)
     }
-    pub async fn fetch_relationships(self) -> Result<Vec<user::Relationship>, RevoltRsError> {
-        let url = format!("{0}/users/relationships", API_URL);
-        let data = self.client.get(url).send().await?.text().await?;
+    pub async fn fetch_relationships(&self) -> Result<Vec<user::Relationship>, RevoltRsError> {
+        let url = format!("{0}/users/relationships", self.api_url());
+        let data = self.get(url).send().await?.text().await?;
         let relationships: Vec<user::Relationship> = serde_json::from_str(&data)?;
         Ok(relationships)
     }
     pub async fn fetch_relationship(
-        self,
+        &self,
         user_id: &str,
     ) -> Result<user::Relationship, RevoltRsError> {
-        let url = format!("{0}/users/{1}/relationship", API_URL, user_id);
-        let data = self.client.get(url).send().await?.text().await?;
+        let url = format!("{0}/users/{1}/relationship", self.api_url(), user_id);
+        let data = self.get(url).send().await?.text().await?;
         let relationship: user::Relationship = serde_json::from_str(&data)?;
         Ok(relationship)
     }
     pub async fn send_accept_friend_request(
-        self,
+        &self,
         username: &str,
     ) -> Result<user::Relationship, RevoltRsError> {
-        let url = format!("{0}/users/{1}/friend", API_URL, username);
-        let data = self.client.put(url).send().await?.text().await?;
+        let url = format!("{0}/users/{1}/friend", self.api_url(), username);
+        let data = self.put(url).send().await?.text().await?;
         let relationship: user::Relationship = serde_json::from_str(&data)?;
         Ok(relationship)
     }
     pub async fn deny_remove_friend(
-        self,
+        &self,
         username: &str,
     ) -> Result<user::Relationship, RevoltRsError> {
-        let url = format!("{0}/users/{1}/friend", API_URL, username);
-        let data = self.client.delete(url).send().await?.text().await?;
+        let url = format!("{0}/users/{1}/friend", self.api_url(), username);
+        let data = self.delete(url).send().await?.text().await?;
         let relationship: user::Relationship = serde_json::from_str(&data)?;
         Ok(relationship)
     }
-    pub async fn block_user(self, user_id: &str) -> Result<user::Relationship, RevoltRsError> {
-        let url = format!("{0}/users/{1}/block", API_URL, user_id);
-        let data = self.client.put(url).send().await?.text().await?;
+    pub async fn block_user(&self, user_id: &str) -> Result<user::Relationship, RevoltRsError> {
+        let url = format!("{0}/users/{1}/block", self.api_url(), user_id);
+        let data = self.put(url).send().await?.text().await?;
         let relationship: user::Relationship = serde_json::from_str(&data)?;
         Ok(relationship)
     }
-    pub async fn unblock_user(self, user_id: &str) -> Result<user::Relationship, RevoltRsError> {
-        let url = format!("{0}/users/{1}/block", API_URL, user_id);
-        let data = self.client.delete(url).send().await?.text().await?;
+    pub async fn unblock_user(&self, user_id: &str) -> Result<user::Relationship, RevoltRsError> {
+        let url = format!("{0}/users/{1}/block", self.api_url(), user_id);
+        let data = self.delete(url).send().await?.text().await?;
         let relationship: user::Relationship = serde_json::from_str(&data)?;
         Ok(relationship)
     }
-    pub async fn fetch_channel(self, channel_id: &str) -> Result<channel::Channel, RevoltRsError> {
-        let url = format!("{0}/channels/{1}", API_URL, channel_id);
-        let data = self.client.get(url).send().await?.text().await?;
+    pub async fn fetch_channel(&self, channel_id: &str) -> Result<channel::Channel, RevoltRsError> {
+        if let Some(cache) = self.cache() {
+            if let Ok(Some(channel)) = cache.channel(channel_id).await {
+                return Ok(channel);
+            }
+        }
+        let url = format!("{0}/channels/{1}", self.api_url(), channel_id);
+        let data = self.get(url).send().await?.text().await?;
         let channel: channel::Channel = serde_json::from_str(&data)?;
+        if let Some(cache) = self.cache() {
+            let _ = cache.insert_channel(channel.clone()).await;
+        }
         Ok(channel)
     }
     pub async fn edit_channel(
-        self,
+        &self,
         channel_id: &str,
         name: Option<String>,
         description: Option<String>,
         icon: Option<String>,
         remove: Option<String>,
     ) -> Result<String, RevoltRsError> {
-        let url = format!("{0}/channels/{1}", API_URL, channel_id);
+        let url = format!("{0}/channels/{1}", self.api_url(), channel_id);
         let parameters = internal::EditAutumn {
-            name: name,
-            description: description,
-            icon: icon,
-            remove: remove,
+            name,
+            description,
+            icon,
+            remove,
         };
         let data = self
-            .client
             .patch(url)
             .body(serde_json::to_string(&parameters)?)
             .send()
@@ -193,33 +420,32 @@ impl RevoltRs {
             .await?;
         Ok(data)
     }
-    pub async fn close_channel(self, channel_id: &str) -> Result<String, RevoltRsError> {
-        let url = format!("{0}/channels/{1}", API_URL, channel_id);
-        let data = self.client.delete(url).send().await?.text().await?;
+    pub async fn close_channel(&self, channel_id: &str) -> Result<String, RevoltRsError> {
+        let url = format!("{0}/channels/{1}", self.api_url(), channel_id);
+        let data = self.delete(url).send().await?.text().await?;
         Ok(data)
     }
-    pub async fn create_invite(self, channel_id: &str) -> Result<String, RevoltRsError> {
-        let url = format!("{0}/channels/{1}/invites", API_URL, channel_id);
-        let data = self.client.post(url).send().await?.text().await?;
+    pub async fn create_invite(&self, channel_id: &str) -> Result<String, RevoltRsError> {
+        let url = format!("{0}/channels/{1}/invites", self.api_url(), channel_id);
+        let data = self.post(url).send().await?.text().await?;
         let invite_struct: internal::Invite = serde_json::from_str(&data)?;
         let invite = invite_struct.code;
         Ok(invite)
     }
     pub async fn set_role_permission(
-        self,
+        &self,
         channel_id: &str,
         role_id: &str,
-        permissions: i32,
+        permissions: ChannelPermission,
     ) -> Result<String, RevoltRsError> {
         let url = format!(
             "{0}/channels/{1}/permissions/{2}",
-            API_URL, channel_id, role_id
+            self.api_url(), channel_id, role_id
         );
         let permission_struct = internal::Permissions {
-            permissions: permissions,
+            permissions,
         };
         let data = self
-            .client
             .post(url)
             .body(serde_json::to_string(&permission_struct)?)
             .send()
@@ -229,16 +455,15 @@ impl RevoltRs {
         Ok(data)
     }
     pub async fn set_default_permission(
-        self,
+        &self,
         channel_id: &str,
-        permissions: i32,
+        permissions: ChannelPermission,
     ) -> Result<String, RevoltRsError> {
-        let url = format!("{0}/channels/{1}/permissions/default", API_URL, channel_id);
+        let url = format!("{0}/channels/{1}/permissions/default", self.api_url(), channel_id);
         let permission_struct = internal::Permissions {
-            permissions: permissions,
+            permissions,
         };
         let data = self
-            .client
             .post(url)
             .body(serde_json::to_string(&permission_struct)?)
             .send()
@@ -248,23 +473,23 @@ impl RevoltRs {
         Ok(data)
     }
     pub async fn send_message(
-        self,
+        &self,
         channel_id: &str,
         content: &str,
         attachments: Option<Vec<String>>,
         replies: Option<message::Reply>,
     ) -> Result<message::Message, RevoltRsError> {
-        let url = format!("{0}/channels/{1}/messages", API_URL, channel_id);
+        let url = format!("{0}/channels/{1}/messages", self.api_url(), channel_id);
         let parameters = internal::SendMessage {
             content: content.to_string(),
             nonce: generate_ulid_string(),
-            attachments: attachments,
-            replies: replies,
+            attachments,
+            replies,
         };
         let data = self
-            .client
             .post(url)
             .body(serde_json::to_string(&parameters)?)
+            .retry_safe()
             .send()
             .await?
             .text()
@@ -272,8 +497,9 @@ impl RevoltRs {
         let my_message = serde_json::from_str(&data)?;
         Ok(my_message)
     }
+    #[allow(clippy::too_many_arguments)]
     pub async fn fetch_messages(
-        self,
+        &self,
         channel_id: &str,
         limit: Option<i32>,
         before: Option<String>,
@@ -282,35 +508,68 @@ impl RevoltRs {
         nearby: Option<String>,
         include_users: Option<bool>,
     ) -> Result<message::Messages, RevoltRsError> {
-        let url = format!("{0}/channels/{1}/messages", API_URL, channel_id);
-        let parameters = internal::SearchMessages {
-            query: None,
-            limit: limit,
-            before: before,
-            after: after,
-            sort: sort,
-            nearby: nearby,
-            include_users: include_users,
+        let url = format!("{0}/channels/{1}/messages", self.api_url(), channel_id);
+        let parameters = internal::FetchMessages {
+            limit,
+            before,
+            after,
+            sort: Some(sort),
+            nearby,
+            include_users,
         };
         let data = self
-            .client
             .get(url)
+            .query(&parameters)
+            .send()
+            .await?
+            .text()
+            .await?;
+        let messages: internal::FetchedMessages = serde_json::from_str(&data)?;
+        Ok(messages.into())
+    }
+    /// Searches the messages of a channel for `query`. Sort by
+    /// `SearchSort::Relevance` to get the best matches first.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn search_messages(
+        &self,
+        channel_id: &str,
+        query: &str,
+        sort: message::SearchSort,
+        limit: Option<i32>,
+        before: Option<String>,
+        after: Option<String>,
+        include_users: Option<bool>,
+    ) -> Result<message::Messages, RevoltRsError> {
+        let url = format!("{0}/channels/{1}/search", self.api_url(), channel_id);
+        let parameters = internal::SearchMessages {
+            query: query.to_string(),
+            limit,
+            before,
+            after,
+            sort,
+            include_users,
+        };
+        let data = self
+            .post(url)
             .body(serde_json::to_string(&parameters)?)
             .send()
             .await?
             .text()
             .await?;
-        let messages: message::Messages = serde_json::from_str(&data)?;
-        Ok(messages)
+        let messages: internal::FetchedMessages = serde_json::from_str(&data)?;
+        Ok(messages.into())
+    }
+    /// Pages through the messages of a channel as a stream.
+    pub fn message_history(&self, channel_id: &str) -> MessageHistory {
+        MessageHistory::new(self.clone(), channel_id)
     }
     pub async fn fetch_message(
-        self,
+        &self,
         channel_id: &str,
         message_id: &str,
     ) -> Result<message::Message, RevoltRsError> {
-        let url = format!("{0}/channels/{1}/messages/{2}", API_URL, channel_id, message_id);
+        let url = format!("{0}/channels/{1}/messages/{2}", self.api_url(), channel_id, message_id);
         let data = self
-            .client
             .get(url)
             .send()
             .await?
@@ -320,17 +579,16 @@ impl RevoltRs {
         Ok(message)
     }
     pub async fn edit_message(
-        self,
+        &self,
         channel_id: &str,
         message_id: &str,
         content: &str,
     ) -> Result<String, RevoltRsError> {
-        let url = format!("{0}/channels/{1}/messages/{2}", API_URL, channel_id, message_id);
+        let url = format!("{0}/channels/{1}/messages/{2}", self.api_url(), channel_id, message_id);
         let parameters = internal::EditMessage {
             content: content.to_string(),
         };
         let data = self
-            .client
             .patch(url)
             .body(serde_json::to_string(&parameters)?)
             .send()
@@ -340,13 +598,12 @@ impl RevoltRs {
         Ok(data)
     }
     pub async fn delete_message(
-        self,
+        &self,
         channel_id: &str,
         message_id: &str,
     ) -> Result<String, RevoltRsError> {
-        let url = format!("{0}/channels/{1}/messages/{2}", API_URL, channel_id, message_id);
+        let url = format!("{0}/channels/{1}/messages/{2}", self.api_url(), channel_id, message_id);
         let data = self
-            .client
             .delete(url)
             .send()
             .await?
@@ -354,4 +611,312 @@ impl RevoltRs {
             .await?;
         Ok(data)
     }
+    /// Deletes up to 100 messages at once. The API only bulk deletes messages
+    /// sent in the last week.
+    pub async fn bulk_delete_messages(
+        &self,
+        channel_id: &str,
+        message_ids: Vec<String>,
+    ) -> Result<String, RevoltRsError> {
+        let url = format!("{0}/channels/{1}/messages/bulk", self.api_url(), channel_id);
+        let parameters = internal::BulkDeleteMessages { ids: message_ids };
+        let data = self
+            .delete(url)
+            .body(serde_json::to_string(&parameters)?)
+            .send()
+            .await?
+            .text()
+            .await?;
+        Ok(data)
+    }
+    /// Deletes the messages of a channel matching the filters set on the
+    /// returned `Purge`.
+    pub fn purge(&self, channel_id: &str) -> Purge {
+        Purge::new(self.clone(), channel_id)
+    }
+    pub async fn fetch_server(&self, server_id: &str) -> Result<server::Server, RevoltRsError> {
+        if let Some(cache) = self.cache() {
+            if let Ok(Some(server)) = cache.server(server_id).await {
+                return Ok(server);
+            }
+        }
+        let url = format!("{0}/servers/{1}", self.api_url(), server_id);
+        let data = self.get(url).send().await?.text().await?;
+        let server: server::Server = serde_json::from_str(&data)?;
+        if let Some(cache) = self.cache() {
+            let _ = cache.insert_server(server.clone()).await;
+        }
+        Ok(server)
+    }
+    pub async fn create_server(
+        &self,
+        name: &str,
+        description: Option<String>,
+    ) -> Result<server::Server, RevoltRsError> {
+        let url = format!("{0}/servers/create", self.api_url());
+        let parameters = internal::CreateServer {
+            name: name.to_string(),
+            description,
+            nonce: generate_ulid_string(),
+        };
+        let data = self
+            .post(url)
+            .body(serde_json::to_string(&parameters)?)
+            .retry_safe()
+            .send()
+            .await?
+            .text()
+            .await?;
+        let server: server::Server = serde_json::from_str(&data)?;
+        Ok(server)
+    }
+    #[allow(clippy::too_many_arguments)]
+    pub async fn edit_server(
+        &self,
+        server_id: &str,
+        name: Option<String>,
+        description: Option<String>,
+        icon: Option<String>,
+        banner: Option<String>,
+        categories: Option<Vec<server::Category>>,
+        system_messages: Option<server::SystemMessages>,
+        remove: Option<String>,
+    ) -> Result<String, RevoltRsError> {
+        let url = format!("{0}/servers/{1}", self.api_url(), server_id);
+        let parameters = internal::EditServer {
+            name,
+            description,
+            icon,
+            banner,
+            categories,
+            system_messages,
+            remove,
+        };
+        let data = self
+            .patch(url)
+            .body(serde_json::to_string(&parameters)?)
+            .send()
+            .await?
+            .text()
+            .await?;
+        Ok(data)
+    }
+    /// Deletes the server if the client owns it, otherwise the client leaves it.
+    pub async fn delete_server(&self, server_id: &str) -> Result<String, RevoltRsError> {
+        let url = format!("{0}/servers/{1}", self.api_url(), server_id);
+        let data = self.delete(url).send().await?.text().await?;
+        Ok(data)
+    }
+    pub async fn leave_server(&self, server_id: &str) -> Result<String, RevoltRsError> {
+        self.delete_server(server_id).await
+    }
+    pub async fn create_channel(
+        &self,
+        server_id: &str,
+        channel_type: channel::ServerChannelType,
+        name: &str,
+        description: Option<String>,
+    ) -> Result<channel::Channel, RevoltRsError> {
+        let url = format!("{0}/servers/{1}/channels", self.api_url(), server_id);
+        let parameters = internal::CreateChannel {
+            channel_type,
+            name: name.to_string(),
+            description,
+            nonce: generate_ulid_string(),
+        };
+        let data = self
+            .post(url)
+            .body(serde_json::to_string(&parameters)?)
+            .retry_safe()
+            .send()
+            .await?
+            .text()
+            .await?;
+        let channel: channel::Channel = serde_json::from_str(&data)?;
+        Ok(channel)
+    }
+    pub async fn fetch_members(&self, server_id: &str) -> Result<server::Members, RevoltRsError> {
+        let url = format!("{0}/servers/{1}/members", self.api_url(), server_id);
+        let data = self.get(url).send().await?.text().await?;
+        let members: server::Members = serde_json::from_str(&data)?;
+        Ok(members)
+    }
+    pub async fn fetch_member(
+        &self,
+        server_id: &str,
+        user_id: &str,
+    ) -> Result<server::Member, RevoltRsError> {
+        if let Some(cache) = self.cache() {
+            if let Ok(Some(member)) = cache.member(server_id, user_id).await {
+                return Ok(member);
+            }
+        }
+        let url = format!("{0}/servers/{1}/members/{2}", self.api_url(), server_id, user_id);
+        let data = self.get(url).send().await?.text().await?;
+        let member: server::Member = serde_json::from_str(&data)?;
+        if let Some(cache) = self.cache() {
+            let _ = cache.insert_member(member.clone()).await;
+        }
+        Ok(member)
+    }
+    pub async fn edit_member(
+        &self,
+        server_id: &str,
+        user_id: &str,
+        nickname: Option<String>,
+        avatar: Option<String>,
+        roles: Option<Vec<String>>,
+        remove: Option<String>,
+    ) -> Result<String, RevoltRsError> {
+        let url = format!("{0}/servers/{1}/members/{2}", self.api_url(), server_id, user_id);
+        let parameters = internal::EditMember {
+            nickname,
+            avatar,
+            roles,
+            remove,
+        };
+        let data = self
+            .patch(url)
+            .body(serde_json::to_string(&parameters)?)
+            .send()
+            .await?
+            .text()
+            .await?;
+        Ok(data)
+    }
+    pub async fn kick_member(&self, server_id: &str, user_id: &str) -> Result<String, RevoltRsError> {
+        let url = format!("{0}/servers/{1}/members/{2}", self.api_url(), server_id, user_id);
+        let data = self.delete(url).send().await?.text().await?;
+        Ok(data)
+    }
+    pub async fn ban_user(
+        &self,
+        server_id: &str,
+        user_id: &str,
+        reason: Option<String>,
+    ) -> Result<String, RevoltRsError> {
+        let url = format!("{0}/servers/{1}/bans/{2}", self.api_url(), server_id, user_id);
+        let parameters = internal::BanUser { reason };
+        let data = self
+            .put(url)
+            .body(serde_json::to_string(&parameters)?)
+            .send()
+            .await?
+            .text()
+            .await?;
+        Ok(data)
+    }
+    pub async fn unban_user(&self, server_id: &str, user_id: &str) -> Result<String, RevoltRsError> {
+        let url = format!("{0}/servers/{1}/bans/{2}", self.api_url(), server_id, user_id);
+        let data = self.delete(url).send().await?.text().await?;
+        Ok(data)
+    }
+    pub async fn fetch_bans(&self, server_id: &str) -> Result<server::Bans, RevoltRsError> {
+        let url = format!("{0}/servers/{1}/bans", self.api_url(), server_id);
+        let data = self.get(url).send().await?.text().await?;
+        let bans: server::Bans = serde_json::from_str(&data)?;
+        Ok(bans)
+    }
+    pub async fn create_role(
+        &self,
+        server_id: &str,
+        name: &str,
+    ) -> Result<server::NewRole, RevoltRsError> {
+        let url = format!("{0}/servers/{1}/roles", self.api_url(), server_id);
+        let parameters = internal::CreateRole {
+            name: name.to_string(),
+        };
+        let data = self
+            .post(url)
+            .body(serde_json::to_string(&parameters)?)
+            .send()
+            .await?
+            .text()
+            .await?;
+        let role: server::NewRole = serde_json::from_str(&data)?;
+        Ok(role)
+    }
+    #[allow(clippy::too_many_arguments)]
+    pub async fn edit_role(
+        &self,
+        server_id: &str,
+        role_id: &str,
+        name: Option<String>,
+        colour: Option<String>,
+        hoist: Option<bool>,
+        rank: Option<i32>,
+        remove: Option<String>,
+    ) -> Result<String, RevoltRsError> {
+        let url = format!("{0}/servers/{1}/roles/{2}", self.api_url(), server_id, role_id);
+        let parameters = internal::EditRole {
+            name,
+            colour,
+            hoist,
+            rank,
+            remove,
+        };
+        let data = self
+            .patch(url)
+            .body(serde_json::to_string(&parameters)?)
+            .send()
+            .await?
+            .text()
+            .await?;
+        Ok(data)
+    }
+    pub async fn delete_role(&self, server_id: &str, role_id: &str) -> Result<String, RevoltRsError> {
+        let url = format!("{0}/servers/{1}/roles/{2}", self.api_url(), server_id, role_id);
+        let data = self.delete(url).send().await?.text().await?;
+        Ok(data)
+    }
+    /// Sets the `(server, channel)` permissions of a role server-wide.
+    pub async fn set_server_role_permission(
+        &self,
+        server_id: &str,
+        role_id: &str,
+        permissions: (ServerPermission, ChannelPermission),
+    ) -> Result<String, RevoltRsError> {
+        let url = format!(
+            "{0}/servers/{1}/permissions/{2}",
+            self.api_url(),
+            server_id,
+            role_id
+        );
+        let parameters = internal::SetServerPermissions {
+            permissions: internal::ServerPermissions {
+                server: permissions.0,
+                channel: permissions.1,
+            },
+        };
+        let data = self
+            .put(url)
+            .body(serde_json::to_string(&parameters)?)
+            .send()
+            .await?
+            .text()
+            .await?;
+        Ok(data)
+    }
+    /// Sets the `(server, channel)` permissions every member of the server has.
+    pub async fn set_server_default_permission(
+        &self,
+        server_id: &str,
+        permissions: (ServerPermission, ChannelPermission),
+    ) -> Result<String, RevoltRsError> {
+        let url = format!("{0}/servers/{1}/permissions/default", self.api_url(), server_id);
+        let parameters = internal::SetServerPermissions {
+            permissions: internal::ServerPermissions {
+                server: permissions.0,
+                channel: permissions.1,
+            },
+        };
+        let data = self
+            .put(url)
+            .body(serde_json::to_string(&parameters)?)
+            .send()
+            .await?
+            .text()
+            .await?;
+        Ok(data)
+    }
 }
@@ -0,0 +1,445 @@
+use crate::data::channel::{Channel, ChannelTypes};
+use crate::data::permissions::{ChannelPermission, ServerPermission, UserPermission};
+use crate::data::server::{Member, Role, Server};
+
+/// What recipients of a direct message may do if they are allowed to message each other.
+pub const DEFAULT_PERMISSION_DM: ChannelPermission = ChannelPermission::from_bits_truncate(
+    ChannelPermission::VIEW.bits()
+        | ChannelPermission::SEND_MESSAGE.bits()
+        | ChannelPermission::MANAGE_CHANNEL.bits()
+        | ChannelPermission::VOICE_CALL.bits()
+        | ChannelPermission::INVITE_OTHERS.bits()
+        | ChannelPermission::EMBED_LINKS.bits()
+        | ChannelPermission::UPLOAD_FILES.bits(),
+);
+
+/// What members of a group may do unless the group sets its own permissions.
+pub const DEFAULT_PERMISSION_GROUP: ChannelPermission = ChannelPermission::from_bits_truncate(
+    ChannelPermission::VIEW.bits()
+        | ChannelPermission::SEND_MESSAGE.bits()
+        | ChannelPermission::VOICE_CALL.bits()
+        | ChannelPermission::INVITE_OTHERS.bits()
+        | ChannelPermission::EMBED_LINKS.bits()
+        | ChannelPermission::UPLOAD_FILES.bits(),
+);
+
+/// Works out the effective permissions of a user, following the same rules as
+/// the Revolt API:
+///
+/// - the owner of a server, group or saved messages channel can do anything,
+/// - server members start from the default permissions and gain the
+///   permissions of each of their roles, applied from the lowest to the
+///   highest ranked role,
+/// - in server channels, the channel's default and per-role permissions
+///   replace the server-wide ones, and nothing is granted without `VIEW`,
+/// - direct message recipients get [`DEFAULT_PERMISSION_DM`] while they may
+///   message each other and only `VIEW` otherwise.
+pub struct PermissionCalculator<'a> {
+    user: &'a str,
+    server: Option<&'a Server>,
+    channel: Option<&'a Channel>,
+    member: Option<&'a Member>,
+    user_permissions: UserPermission,
+}
+
+impl<'a> PermissionCalculator<'a> {
+    pub fn new(user_id: &'a str) -> Self {
+        PermissionCalculator {
+            user: user_id,
+            server: None,
+            channel: None,
+            member: None,
+            user_permissions: UserPermission::all(),
+        }
+    }
+    pub fn server(mut self, server: &'a Server) -> Self {
+        self.server = Some(server);
+        self
+    }
+    pub fn channel(mut self, channel: &'a Channel) -> Self {
+        self.channel = Some(channel);
+        self
+    }
+    pub fn member(mut self, member: &'a Member) -> Self {
+        self.member = Some(member);
+        self
+    }
+    /// What the user may do to the other recipient of a direct message, which
+    /// depends on their relationship. Defaults to everything.
+    pub fn user_permissions(mut self, permissions: UserPermission) -> Self {
+        self.user_permissions = permissions;
+        self
+    }
+    fn is_server_owner(&self) -> bool {
+        self.server.is_some_and(|server| server.owner == self.user)
+    }
+    /// The member's roles ordered from the lowest to the highest ranked one.
+    pub fn roles(&self) -> Vec<(&'a str, &'a Role)> {
+        let (server, member) = match (self.server, self.member) {
+            (Some(server), Some(member)) if member.id.user == self.user => (server, member),
+            _ => return Vec::new(),
+        };
+        let mut roles: Vec<(&'a str, &'a Role)> = member
+            .roles
+            .iter()
+            .flatten()
+            .filter_map(|id| {
+                let role = server.roles.as_ref()?.get(id)?;
+                Some((id.as_str(), role))
+            })
+            .collect();
+        roles.sort_by_key(|(_, role)| std::cmp::Reverse(role.rank.unwrap_or(i32::MAX)));
+        roles
+    }
+    pub fn server_permissions(&self) -> ServerPermission {
+        let server = match self.server {
+            Some(server) => server,
+            None => return ServerPermission::empty(),
+        };
+        if self.is_server_owner() {
+            return ServerPermission::all();
+        }
+        if self.member.is_none_or(|member| member.id.user != self.user) {
+            return ServerPermission::empty();
+        }
+        self.roles()
+            .into_iter()
+            .fold(server.default_permissions.0, |permissions, (_, role)| {
+                permissions | role.permissions.0
+            })
+    }
+    pub fn channel_permissions(&self) -> ChannelPermission {
+        let channel = match self.channel {
+            Some(channel) => channel,
+            None => return ChannelPermission::empty(),
+        };
+        let is_recipient = channel
+            .recipients
+            .as_ref()
+            .is_some_and(|recipients| recipients.iter().any(|id| id == self.user));
+        match channel.channel_type {
+            ChannelTypes::SavedMessages => {
+                if channel.user.as_deref() == Some(self.user) {
+                    ChannelPermission::all()
+                } else {
+                    ChannelPermission::empty()
+                }
+            }
+            ChannelTypes::DirectMessage => {
+                if !is_recipient {
+                    ChannelPermission::empty()
+                } else if self.user_permissions.contains(UserPermission::SEND_MESSAGE) {
+                    DEFAULT_PERMISSION_DM
+                } else {
+                    ChannelPermission::VIEW
+                }
+            }
+            ChannelTypes::Group => {
+                if channel.owner.as_deref() == Some(self.user) {
+                    ChannelPermission::all()
+                } else if is_recipient {
+                    channel.permissions.unwrap_or(DEFAULT_PERMISSION_GROUP)
+                } else {
+                    ChannelPermission::empty()
+                }
+            }
+            ChannelTypes::TextChannel | ChannelTypes::VoiceChannel => {
+                let server = match self.server {
+                    Some(server) if channel.server.as_deref() == Some(server.id.as_str()) => server,
+                    _ => return ChannelPermission::empty(),
+                };
+                if self.is_server_owner() {
+                    return ChannelPermission::all();
+                }
+                if !self.server_permissions().contains(ServerPermission::VIEW) {
+                    return ChannelPermission::empty();
+                }
+                let default = channel
+                    .default_permissions
+                    .unwrap_or(server.default_permissions.1);
+                let permissions =
+                    self.roles()
+                        .into_iter()
+                        .fold(default, |permissions, (id, role)| {
+                            let role_permissions = channel
+                                .role_permissions
+                                .as_ref()
+                                .and_then(|overrides| overrides.get(id))
+                                .copied()
+                                .unwrap_or(role.permissions.1);
+                            permissions | role_permissions
+                        });
+                if permissions.contains(ChannelPermission::VIEW) {
+                    permissions
+                } else {
+                    ChannelPermission::empty()
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::server::MemberId;
+    use std::collections::HashMap;
+
+    const OWNER: &str = "01FD58YK5W7QRV5H3D64KTQYX3";
+    const USER: &str = "01FDAD6EVAJ4VWHQH0GQCS9S9K";
+    const OTHER: &str = "01FEDXEK0MN4TQ9VBX6NZ6GB6K";
+    const SERVER: &str = "01FDAD7Z1HTRS4S3XX5FT0R8F2";
+
+    fn server(roles: Vec<(&str, Role)>) -> Server {
+        Server {
+            id: SERVER.to_string(),
+            nonce: None,
+            owner: OWNER.to_string(),
+            name: "server".to_string(),
+            description: None,
+            channels: Vec::new(),
+            categories: None,
+            system_messages: None,
+            roles: Some(
+                roles
+                    .into_iter()
+                    .map(|(id, role)| (id.to_string(), role))
+                    .collect(),
+            ),
+            default_permissions: (
+                ServerPermission::VIEW | ServerPermission::CHANGE_NICKNAME,
+                ChannelPermission::VIEW | ChannelPermission::SEND_MESSAGE,
+            ),
+            icon: None,
+            banner: None,
+        }
+    }
+
+    fn role(rank: i32, server: ServerPermission, channel: ChannelPermission) -> Role {
+        Role {
+            name: "role".to_string(),
+            permissions: (server, channel),
+            colour: None,
+            hoist: None,
+            rank: Some(rank),
+        }
+    }
+
+    fn member(user: &str, roles: &[&str]) -> Member {
+        Member {
+            id: MemberId {
+                server: SERVER.to_string(),
+                user: user.to_string(),
+            },
+            nickname: None,
+            avatar: None,
+            roles: Some(roles.iter().map(|role| role.to_string()).collect()),
+        }
+    }
+
+    fn channel(channel_type: ChannelTypes) -> Channel {
+        let server = match channel_type {
+            ChannelTypes::TextChannel | ChannelTypes::VoiceChannel => Some(SERVER.to_string()),
+            _ => None,
+        };
+        Channel {
+            id: "01FDAD81Z2GG7Q3X4B2VHQ2Z6J".to_string(),
+            server,
+            channel_type,
+            active: None,
+            recipients: Some(vec![USER.to_string(), OTHER.to_string()]),
+            name: None,
+            owner: None,
+            description: None,
+            last_message: None,
+            user: None,
+            icon: None,
+            default_permissions: None,
+            role_permissions: None,
+            permissions: None,
+            nonce: None,
+        }
+    }
+
+    #[test]
+    fn server_owner_has_every_permission() {
+        let server = server(Vec::new());
+        let channel = channel(ChannelTypes::TextChannel);
+        let calculator = PermissionCalculator::new(OWNER)
+            .server(&server)
+            .channel(&channel);
+        assert_eq!(calculator.server_permissions(), ServerPermission::all());
+        assert_eq!(calculator.channel_permissions(), ChannelPermission::all());
+    }
+
+    #[test]
+    fn members_gain_the_permissions_of_their_roles() {
+        let server = server(vec![
+            (
+                "MOD",
+                role(
+                    1,
+                    ServerPermission::KICK_MEMBERS,
+                    ChannelPermission::MANAGE_MESSAGES,
+                ),
+            ),
+            (
+                "ADMIN",
+                role(0, ServerPermission::BAN_MEMBERS, ChannelPermission::empty()),
+            ),
+            (
+                "UNUSED",
+                role(
+                    2,
+                    ServerPermission::MANAGE_SERVER,
+                    ChannelPermission::empty(),
+                ),
+            ),
+        ]);
+        let channel = channel(ChannelTypes::TextChannel);
+        let member = member(USER, &["ADMIN", "MOD", "MISSING"]);
+        let calculator = PermissionCalculator::new(USER)
+            .server(&server)
+            .channel(&channel)
+            .member(&member);
+        let roles: Vec<&str> = calculator.roles().into_iter().map(|(id, _)| id).collect();
+        assert_eq!(roles, ["MOD", "ADMIN"]);
+        assert_eq!(
+            calculator.server_permissions(),
+            ServerPermission::VIEW
+                | ServerPermission::CHANGE_NICKNAME
+                | ServerPermission::KICK_MEMBERS
+                | ServerPermission::BAN_MEMBERS
+        );
+        assert_eq!(
+            calculator.channel_permissions(),
+            ChannelPermission::VIEW
+                | ChannelPermission::SEND_MESSAGE
+                | ChannelPermission::MANAGE_MESSAGES
+        );
+    }
+
+    #[test]
+    fn non_members_have_no_permissions() {
+        let server = server(Vec::new());
+        let channel = channel(ChannelTypes::TextChannel);
+        let member = member(OTHER, &[]);
+        let without_member = PermissionCalculator::new(USER)
+            .server(&server)
+            .channel(&channel);
+        assert_eq!(
+            without_member.server_permissions(),
+            ServerPermission::empty()
+        );
+        assert_eq!(
+            without_member.channel_permissions(),
+            ChannelPermission::empty()
+        );
+        let other_member = PermissionCalculator::new(USER)
+            .server(&server)
+            .channel(&channel)
+            .member(&member);
+        assert_eq!(other_member.server_permissions(), ServerPermission::empty());
+        assert!(other_member.roles().is_empty());
+    }
+
+    #[test]
+    fn channel_overrides_replace_server_permissions() {
+        let server = server(vec![(
+            "MOD",
+            role(
+                0,
+                ServerPermission::empty(),
+                ChannelPermission::MANAGE_MESSAGES,
+            ),
+        )]);
+        let mut channel = channel(ChannelTypes::TextChannel);
+        channel.default_permissions = Some(ChannelPermission::VIEW);
+        let mut overrides = HashMap::new();
+        overrides.insert("MOD".to_string(), ChannelPermission::UPLOAD_FILES);
+        channel.role_permissions = Some(overrides);
+        let member = member(USER, &["MOD"]);
+        let calculator = PermissionCalculator::new(USER)
+            .server(&server)
+            .channel(&channel)
+            .member(&member);
+        assert_eq!(
+            calculator.channel_permissions(),
+            ChannelPermission::VIEW | ChannelPermission::UPLOAD_FILES
+        );
+    }
+
+    #[test]
+    fn server_channels_require_view() {
+        let server = server(Vec::new());
+        let mut without_view = channel(ChannelTypes::TextChannel);
+        without_view.default_permissions = Some(ChannelPermission::SEND_MESSAGE);
+        let member = member(USER, &[]);
+        let calculator = PermissionCalculator::new(USER)
+            .server(&server)
+            .channel(&without_view)
+            .member(&member);
+        assert_eq!(calculator.channel_permissions(), ChannelPermission::empty());
+
+        let mut hidden = server.clone();
+        hidden.default_permissions = (ServerPermission::empty(), ChannelPermission::all());
+        let channel = channel(ChannelTypes::TextChannel);
+        let calculator = PermissionCalculator::new(USER)
+            .server(&hidden)
+            .channel(&channel)
+            .member(&member);
+        assert_eq!(calculator.channel_permissions(), ChannelPermission::empty());
+    }
+
+    #[test]
+    fn channels_of_another_server_grant_nothing() {
+        let server = server(Vec::new());
+        let mut channel = channel(ChannelTypes::TextChannel);
+        channel.server = Some(OTHER.to_string());
+        let member = member(USER, &[]);
+        let calculator = PermissionCalculator::new(USER)
+            .server(&server)
+            .channel(&channel)
+            .member(&member);
+        assert_eq!(calculator.channel_permissions(), ChannelPermission::empty());
+    }
+
+    #[test]
+    fn direct_messages_depend_on_the_relationship() {
+        let channel = channel(ChannelTypes::DirectMessage);
+        let calculator = PermissionCalculator::new(USER).channel(&channel);
+        assert_eq!(calculator.channel_permissions(), DEFAULT_PERMISSION_DM);
+        let calculator = PermissionCalculator::new(USER)
+            .channel(&channel)
+            .user_permissions(UserPermission::ACCESS | UserPermission::VIEW_PROFILE);
+        assert_eq!(calculator.channel_permissions(), ChannelPermission::VIEW);
+        let calculator = PermissionCalculator::new(OWNER).channel(&channel);
+        assert_eq!(calculator.channel_permissions(), ChannelPermission::empty());
+    }
+
+    #[test]
+    fn groups_use_their_own_permissions() {
+        let mut channel = channel(ChannelTypes::Group);
+        channel.owner = Some(OWNER.to_string());
+        let calculator = PermissionCalculator::new(USER).channel(&channel);
+        assert_eq!(calculator.channel_permissions(), DEFAULT_PERMISSION_GROUP);
+        let calculator = PermissionCalculator::new(OWNER).channel(&channel);
+        assert_eq!(calculator.channel_permissions(), ChannelPermission::all());
+
+        channel.permissions = Some(ChannelPermission::VIEW);
+        let calculator = PermissionCalculator::new(USER).channel(&channel);
+        assert_eq!(calculator.channel_permissions(), ChannelPermission::VIEW);
+        let calculator = PermissionCalculator::new(SERVER).channel(&channel);
+        assert_eq!(calculator.channel_permissions(), ChannelPermission::empty());
+    }
+
+    #[test]
+    fn saved_messages_belong_to_their_user() {
+        let mut channel = channel(ChannelTypes::SavedMessages);
+        channel.user = Some(USER.to_string());
+        let calculator = PermissionCalculator::new(USER).channel(&channel);
+        assert_eq!(calculator.channel_permissions(), ChannelPermission::all());
+        let calculator = PermissionCalculator::new(OTHER).channel(&channel);
+        assert_eq!(calculator.channel_permissions(), ChannelPermission::empty());
+    }
+}
@@ -0,0 +1,133 @@
+use crate::data::message::{ContentType, Message};
+use crate::{MessageHistory, RevoltRs, RevoltRsError};
+use futures::StreamExt;
+use regex::Regex;
+use rusty_ulid::Ulid;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const BULK_DELETE_LIMIT: usize = 100;
+/// How old a message may be to still be bulk deleted, a little under the
+/// week the API allows so messages do not age out while a purge runs.
+const BULK_DELETE_MAX_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60 - 10 * 60);
+
+type Predicate = dyn Fn(&Message) -> bool + Send + Sync;
+
+/// Walks the history of a channel, newest first, and deletes the messages
+/// matching every filter that was set.
+///
+/// Recent messages are deleted in bulk, older ones one at a time.
+pub struct Purge {
+    revolt: RevoltRs,
+    history: MessageHistory,
+    filters: Vec<Box<Predicate>>,
+    limit: Option<usize>,
+}
+
+impl Purge {
+    pub(crate) fn new(revolt: RevoltRs, channel_id: &str) -> Self {
+        Purge {
+            history: revolt.message_history(channel_id),
+            revolt,
+            filters: Vec::new(),
+            limit: None,
+        }
+    }
+    pub fn author(self, user_id: &str) -> Self {
+        let user_id = user_id.to_string();
+        self.filter(move |message| message.author == user_id)
+    }
+    /// Only deletes messages sent after `time`.
+    pub fn since(mut self, time: SystemTime) -> Self {
+        self.history = self.history.since(time);
+        self
+    }
+    /// Only deletes messages sent before `time`.
+    pub fn until(mut self, time: SystemTime) -> Self {
+        self.history = self.history.until(time);
+        self
+    }
+    /// Only deletes messages whose text matches `regex`.
+    pub fn content(self, regex: Regex) -> Self {
+        self.filter(move |message| match &message.content {
+            Some(ContentType::B(content)) => regex.is_match(content),
+            _ => false,
+        })
+    }
+    pub fn has_attachments(self, has_attachments: bool) -> Self {
+        self.filter(move |message| {
+            let attachments = message.attachments.as_ref();
+            attachments.is_some_and(|attachments| !attachments.is_empty()) == has_attachments
+        })
+    }
+    pub fn filter<F>(mut self, filter: F) -> Self
+    where
+        F: Fn(&Message) -> bool + Send + Sync + 'static,
+    {
+        self.filters.push(Box::new(filter));
+        self
+    }
+    /// Stops after deleting `limit` messages.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+    /// Runs the purge, returning how many messages were deleted.
+    pub async fn run(self) -> Result<usize, RevoltRsError> {
+        let Purge {
+            revolt,
+            history,
+            filters,
+            limit,
+        } = self;
+        let mut history = history.stream();
+        let mut batch: Vec<Message> = Vec::new();
+        let mut deleted = 0;
+        while limit.is_none_or(|limit| deleted + batch.len() < limit) {
+            let message = match history.next().await {
+                Some(message) => message?,
+                None => break,
+            };
+            if !filters.iter().all(|filter| filter(&message)) {
+                continue;
+            }
+            if is_recent(&message.id) {
+                batch.push(message);
+                if batch.len() == BULK_DELETE_LIMIT {
+                    deleted += delete(&revolt, &mut batch).await?;
+                }
+            } else {
+                revolt.delete_message(&message.channel, &message.id).await?;
+                deleted += 1;
+            }
+        }
+        deleted += delete(&revolt, &mut batch).await?;
+        Ok(deleted)
+    }
+}
+
+/// Deletes a batch of recent messages, in bulk when there is more than one.
+async fn delete(revolt: &RevoltRs, batch: &mut Vec<Message>) -> Result<usize, RevoltRsError> {
+    let count = batch.len();
+    match batch.as_slice() {
+        [] => return Ok(0),
+        [message] => {
+            revolt.delete_message(&message.channel, &message.id).await?;
+        }
+        [message, ..] => {
+            let ids = batch.iter().map(|message| message.id.clone()).collect();
+            revolt.bulk_delete_messages(&message.channel, ids).await?;
+        }
+    }
+    batch.clear();
+    Ok(count)
+}
+
+fn is_recent(message_id: &str) -> bool {
+    let sent = match message_id.parse::<Ulid>() {
+        Ok(ulid) => UNIX_EPOCH + Duration::from_millis(ulid.timestamp()),
+        Err(_) => return false,
+    };
+    SystemTime::now()
+        .duration_since(sent)
+        .map_or(true, |age| age < BULK_DELETE_MAX_AGE)
+}
@@ -0,0 +1,291 @@
+use async_std::task;
+use reqwest::header::HeaderMap;
+use reqwest::{Method, Url};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const LIMIT: &str = "x-ratelimit-limit";
+const REMAINING: &str = "x-ratelimit-remaining";
+const RESET_AFTER: &str = "x-ratelimit-reset-after";
+const BUCKET: &str = "x-ratelimit-bucket";
+/// How far apart two reset times may be and still belong to the same window.
+const WINDOW_SLACK: Duration = Duration::from_secs(1);
+/// How often buckets whose window has passed are forgotten.
+const EVICT_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RateLimitInfo {
+    pub(crate) limit: u32,
+    pub(crate) remaining: u32,
+    pub(crate) reset_after: Duration,
+}
+
+impl RateLimitInfo {
+    fn from_headers(headers: &HeaderMap) -> Option<(String, Self)> {
+        let header = |name: &str| headers.get(name)?.to_str().ok();
+        let bucket = header(BUCKET)?.to_string();
+        let info = RateLimitInfo {
+            limit: header(LIMIT)?.parse().ok()?,
+            remaining: header(REMAINING)?.parse().ok()?,
+            reset_after: Duration::from_millis(header(RESET_AFTER)?.parse().ok()?),
+        };
+        Some((bucket, info))
+    }
+}
+
+#[derive(Debug)]
+struct BucketState {
+    limit: u32,
+    remaining: u32,
+    reset_at: Instant,
+    window: Duration,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    state: Mutex<BucketState>,
+}
+
+impl Bucket {
+    fn new(info: RateLimitInfo) -> Self {
+        Bucket {
+            state: Mutex::new(BucketState {
+                limit: info.limit,
+                remaining: info.remaining,
+                reset_at: Instant::now() + info.reset_after,
+                window: info.reset_after,
+            }),
+        }
+    }
+    /// Takes a request from the bucket and waits until the window it belongs
+    /// to has started. When the current window is used up the request is
+    /// booked into the next one, so concurrent requests queue up in order
+    /// without holding the lock while they wait.
+    async fn acquire(&self) {
+        let wait = {
+            let state = &mut *self.state.lock().unwrap();
+            let now = Instant::now();
+            if now >= state.reset_at {
+                state.remaining = state.limit;
+                state.reset_at = now + state.window;
+            }
+            if state.remaining == 0 {
+                state.remaining = state.limit;
+                state.reset_at += state.window;
+            }
+            state.remaining = state.remaining.saturating_sub(1);
+            let start = state.reset_at.checked_sub(state.window);
+            start.map_or(Duration::ZERO, |start| start.saturating_duration_since(now))
+        };
+        if !wait.is_zero() {
+            task::sleep(wait).await;
+        }
+    }
+    /// Whether the window has passed, so the bucket is full again and
+    /// nothing is booked into it.
+    fn is_expired(&self, now: Instant) -> bool {
+        now >= self.state.lock().unwrap().reset_at
+    }
+    /// Takes the state the API reports, unless requests were already booked
+    /// into a later window than the one the response belongs to.
+    fn update(&self, info: RateLimitInfo) {
+        let mut state = self.state.lock().unwrap();
+        let reset_at = Instant::now() + info.reset_after;
+        state.limit = info.limit;
+        state.window = state.window.max(info.reset_after);
+        if state.reset_at > reset_at + WINDOW_SLACK {
+            return;
+        }
+        if reset_at < state.reset_at + WINDOW_SLACK {
+            state.remaining = state.remaining.min(info.remaining);
+        } else {
+            state.remaining = info.remaining;
+        }
+        state.reset_at = reset_at;
+    }
+}
+
+/// Tracks the rate limit buckets Revolt reports, keyed by the bucket name the
+/// API sends back, and which route was last seen using which bucket.
+///
+/// Routes include the id they act on, so buckets whose window has passed are
+/// forgotten along with their routes, and learnt again from the next response.
+#[derive(Debug, Default)]
+pub(crate) struct RateLimiter {
+    routes: Mutex<HashMap<String, String>>,
+    buckets: Mutex<HashMap<String, Arc<Bucket>>>,
+    evicted_at: Mutex<Option<Instant>>,
+}
+
+impl RateLimiter {
+    fn bucket(&self, route: &str) -> Option<Arc<Bucket>> {
+        let name = self.routes.lock().unwrap().get(route)?.clone();
+        self.buckets.lock().unwrap().get(&name).cloned()
+    }
+    pub(crate) async fn acquire(&self, route: &str) {
+        if let Some(bucket) = self.bucket(route) {
+            bucket.acquire().await;
+        }
+    }
+    /// Records the rate limit headers of a response and returns them.
+    pub(crate) fn update(&self, route: &str, headers: &HeaderMap) -> Option<RateLimitInfo> {
+        let (name, info) = RateLimitInfo::from_headers(headers)?;
+        self.routes
+            .lock()
+            .unwrap()
+            .insert(route.to_string(), name.clone());
+        let bucket = self
+            .buckets
+            .lock()
+            .unwrap()
+            .entry(name)
+            .or_insert_with(|| Arc::new(Bucket::new(info)))
+            .clone();
+        bucket.update(info);
+        self.evict();
+        Some(info)
+    }
+    fn evict(&self) {
+        let now = Instant::now();
+        {
+            let mut evicted_at = self.evicted_at.lock().unwrap();
+            if evicted_at.is_some_and(|evicted_at| now < evicted_at + EVICT_INTERVAL) {
+                return;
+            }
+            *evicted_at = Some(now);
+        }
+        self.remove_expired(now);
+    }
+    fn remove_expired(&self, now: Instant) {
+        let mut buckets = self.buckets.lock().unwrap();
+        buckets.retain(|_, bucket| !bucket.is_expired(now));
+        self.routes
+            .lock()
+            .unwrap()
+            .retain(|_, name| buckets.contains_key(name));
+    }
+}
+
+/// Builds the key a request is tracked under: the method and path, keeping the
+/// first ID of the path (the channel, server or user the route acts on) and
+/// replacing every later ID with a placeholder.
+pub(crate) fn route(method: &Method, url: &Url) -> String {
+    let mut seen_id = false;
+    let path: Vec<&str> = url
+        .path_segments()
+        .into_iter()
+        .flatten()
+        .map(|segment| {
+            if !is_id(segment) {
+                segment
+            } else if seen_id {
+                ":id"
+            } else {
+                seen_id = true;
+                segment
+            }
+        })
+        .collect();
+    format!("{0} /{1}", method, path.join("/"))
+}
+
+fn is_id(segment: &str) -> bool {
+    segment.len() == 26 && segment.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHANNEL: &str = "01FD58YK5W7QRV5H3D64KTQYX3";
+    const MESSAGE: &str = "01FDAD6EVAJ4VWHQH0GQCS9S9K";
+
+    fn info(limit: u32, remaining: u32, reset_after: Duration) -> RateLimitInfo {
+        RateLimitInfo {
+            limit,
+            remaining,
+            reset_after,
+        }
+    }
+
+    fn headers(bucket: &str, reset_after: Duration) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(BUCKET, bucket.parse().unwrap());
+        headers.insert(LIMIT, "10".parse().unwrap());
+        headers.insert(REMAINING, "9".parse().unwrap());
+        let reset_after = reset_after.as_millis().to_string();
+        headers.insert(RESET_AFTER, reset_after.parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn routes_keep_the_first_id() {
+        let url = format!(
+            "https://api.revolt.chat/channels/{0}/messages/{1}",
+            CHANNEL, MESSAGE
+        );
+        assert_eq!(
+            route(&Method::DELETE, &url.parse().unwrap()),
+            format!("DELETE /channels/{0}/messages/:id", CHANNEL)
+        );
+        let url = "https://api.revolt.chat/users/@me".parse().unwrap();
+        assert_eq!(route(&Method::GET, &url), "GET /users/@me");
+    }
+
+    #[async_std::test]
+    async fn requests_past_the_limit_wait_for_the_next_window() {
+        let window = Duration::from_millis(100);
+        let start = Instant::now();
+        let bucket = Bucket::new(info(2, 1, window));
+        bucket.acquire().await;
+        assert!(start.elapsed() < window);
+        bucket.acquire().await;
+        assert!(start.elapsed() >= window);
+        let state = bucket.state.lock().unwrap();
+        assert_eq!(state.remaining, 1);
+        assert!(state.reset_at >= start + 2 * window);
+    }
+
+    #[test]
+    fn responses_from_an_older_window_are_ignored() {
+        let window = Duration::from_secs(10);
+        let bucket = Bucket::new(info(5, 0, window));
+        let booked = {
+            let mut state = bucket.state.lock().unwrap();
+            state.reset_at += window;
+            state.reset_at
+        };
+        bucket.update(info(5, 4, window));
+        let state = bucket.state.lock().unwrap();
+        assert_eq!(state.remaining, 0);
+        assert_eq!(state.reset_at, booked);
+    }
+
+    #[test]
+    fn responses_from_the_same_window_only_lower_remaining() {
+        let window = Duration::from_secs(10);
+        let bucket = Bucket::new(info(5, 3, window));
+        bucket.update(info(5, 4, window));
+        assert_eq!(bucket.state.lock().unwrap().remaining, 3);
+        bucket.update(info(5, 1, window));
+        assert_eq!(bucket.state.lock().unwrap().remaining, 1);
+        bucket.update(info(5, 4, 2 * window));
+        assert_eq!(bucket.state.lock().unwrap().remaining, 4);
+    }
+
+    #[test]
+    fn expired_buckets_are_forgotten() {
+        let limiter = RateLimiter::default();
+        let url = format!("https://api.revolt.chat/channels/{0}/messages", CHANNEL);
+        let expired = route(&Method::POST, &url.parse().unwrap());
+        let active = route(&Method::GET, &url.parse().unwrap());
+        limiter.update(&expired, &headers("expired", Duration::ZERO));
+        limiter.update(&active, &headers("active", Duration::from_secs(10)));
+        limiter.remove_expired(Instant::now());
+        assert!(limiter.bucket(&expired).is_none());
+        assert!(limiter.bucket(&active).is_some());
+        assert_eq!(limiter.routes.lock().unwrap().len(), 1);
+        assert_eq!(limiter.buckets.lock().unwrap().len(), 1);
+    }
+}
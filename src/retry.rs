@@ -0,0 +1,51 @@
+use rand::Rng;
+use reqwest::Method;
+use std::time::Duration;
+
+/// How often and how patiently failed requests are retried.
+///
+/// Connection failures and 429 responses are always safe to retry. Timeouts
+/// and 5xx responses are only retried for idempotent requests, or requests
+/// carrying a nonce the API deduplicates on.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn none() -> Self {
+        RetryPolicy {
+            max_retries: 0,
+            ..RetryPolicy::default()
+        }
+    }
+    /// The exponential backoff for the given retry, with half of it jittered.
+    pub(crate) fn backoff(&self, retry: u32) -> Duration {
+        let backoff = self
+            .initial_backoff
+            .checked_mul(2u32.saturating_pow(retry))
+            .unwrap_or(self.max_backoff)
+            .min(self.max_backoff);
+        let half = backoff / 2;
+        half + rand::thread_rng().gen_range(Duration::from_secs(0)..=half)
+    }
+}
+
+pub(crate) fn is_idempotent(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::GET | Method::HEAD | Method::OPTIONS | Method::PUT | Method::DELETE
+    )
+}